@@ -0,0 +1,99 @@
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+// Known answers for one input, stored next to it as '<input stem>.answers':
+// the first line is the part 1 answer and the second line the part 2 answer.
+// A blank or missing line means that answer is not known yet.
+pub(crate) struct Answers {
+    part1: Option<String>,
+    part2: Option<String>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, serde::Serialize)]
+#[serde(rename_all = "lowercase")]
+pub(crate) enum Status {
+    Pass,
+    Fail,
+    Unknown,
+}
+
+impl fmt::Display for Status {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Status::Pass => write!(f, "pass"),
+            Status::Fail => write!(f, "FAIL"),
+            Status::Unknown => write!(f, "-"),
+        }
+    }
+}
+
+impl Answers {
+    pub(crate) fn parse(contents: &str) -> Self {
+        let mut lines = contents.lines().map(str::trim);
+        let mut next = || {
+            lines
+                .next()
+                .filter(|line| !line.is_empty())
+                .map(String::from)
+        };
+        let part1 = next();
+        let part2 = next();
+        Self { part1, part2 }
+    }
+
+    pub(crate) fn part(&self, part: u8) -> Option<&str> {
+        match part {
+            1 => self.part1.as_deref(),
+            2 => self.part2.as_deref(),
+            _ => None,
+        }
+    }
+
+    pub(crate) fn check(&self, part: u8, answer: &str) -> Status {
+        match self.part(part) {
+            Some(expected) if expected == answer => Status::Pass,
+            Some(_) => Status::Fail,
+            None => Status::Unknown,
+        }
+    }
+}
+
+pub(crate) fn sibling_path(input: &Path) -> PathBuf {
+    input.with_extension("answers")
+}
+
+// 'Ok(None)' when the input has no answers file at all.
+pub(crate) fn load(input: &Path) -> Result<Option<Answers>, io::Error> {
+    match fs::read_to_string(sibling_path(input)) {
+        Ok(contents) => Ok(Some(Answers::parse(&contents))),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(None),
+        Err(e) => Err(e),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_check() {
+        let answers = Answers::parse("  24000 \n\n");
+        assert_eq!(answers.part(1), Some("24000"));
+        assert_eq!(answers.part(2), None);
+        assert_eq!(answers.check(1, "24000"), Status::Pass);
+        assert_eq!(answers.check(1, "24001"), Status::Fail);
+        assert_eq!(answers.check(2, "45000"), Status::Unknown);
+        assert_eq!(answers.check(3, "anything"), Status::Unknown);
+
+        // A blank first line leaves part 1 unknown without shifting part 2 into its place.
+        let answers = Answers::parse("\n45000");
+        assert_eq!(answers.part(1), None);
+        assert_eq!(answers.check(2, "45000"), Status::Pass);
+        assert_eq!(
+            sibling_path(Path::new("inputs/day01/alice.txt")),
+            Path::new("inputs/day01/alice.answers")
+        );
+    }
+}
@@ -0,0 +1,94 @@
+use std::fmt;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+// Set once by '--checked' before any day runs. A global rather than a parameter so that the
+// solvers keep their plain 'part1(file_path)' signatures.
+static CHECKED: AtomicBool = AtomicBool::new(false);
+
+pub(crate) fn set_checked(checked: bool) {
+    CHECKED.store(checked, Ordering::Relaxed);
+}
+
+#[derive(Debug)]
+pub(crate) struct OverflowError {
+    accumulator: &'static str,
+    line: Option<usize>,
+}
+
+impl fmt::Display for OverflowError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Overflow in accumulator '{}'", self.accumulator)?;
+        match self.line {
+            Some(line) => write!(f, " at line {line}."),
+            None => write!(f, "."),
+        }
+    }
+}
+
+impl std::error::Error for OverflowError {}
+
+// A named running total. Outside of checked mode additions wrap, exactly as plain '+' does in a
+// release build; in checked mode an overflow is returned as an error naming the accumulator and,
+// where there is one, the input line that caused it.
+pub(crate) struct Accumulator {
+    name: &'static str,
+    total: u64,
+    checked: bool,
+}
+
+impl Accumulator {
+    // Checked or not according to '--checked'.
+    pub(crate) fn new(name: &'static str) -> Self {
+        Self::with_checked(name, CHECKED.load(Ordering::Relaxed))
+    }
+
+    // Checked or not regardless of '--checked', so tests do not depend on (or race over) the global.
+    pub(crate) fn with_checked(name: &'static str, checked: bool) -> Self {
+        Self {
+            name,
+            total: 0,
+            checked,
+        }
+    }
+
+    pub(crate) fn add(&mut self, value: u64, line: Option<usize>) -> Result<(), OverflowError> {
+        self.total = if self.checked {
+            self.total.checked_add(value).ok_or(OverflowError {
+                accumulator: self.name,
+                line,
+            })?
+        } else {
+            self.total.wrapping_add(value)
+        };
+        Ok(())
+    }
+
+    pub(crate) fn total(&self) -> u64 {
+        self.total
+    }
+
+    pub(crate) fn reset(&mut self) {
+        self.total = 0;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_checked_overflow_names_accumulator_and_line() {
+        let mut total = Accumulator::with_checked("score total", true);
+        total.add(u64::MAX, Some(1)).unwrap();
+        let error = total.add(1, Some(2)).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Overflow in accumulator 'score total' at line 2."
+        );
+
+        let mut total = Accumulator::with_checked("score total", false);
+        total.add(u64::MAX, Some(1)).unwrap();
+        total.add(2, Some(2)).unwrap();
+        assert_eq!(total.total(), 1);
+    }
+}
@@ -0,0 +1,131 @@
+use std::env;
+use std::error;
+use std::fs::{self, File};
+use std::io::{self, BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use crate::memory::{self, AllocStats};
+use crate::runner::{Day, Solver};
+
+// A day's benchmark: a generator for inputs of any size, and the implementations to race on it.
+pub(crate) struct Benchmark {
+    // What the generator's size argument counts (e.g. "MB"), and the size used when none is given.
+    pub(crate) unit: &'static str,
+    pub(crate) default_size: u64,
+    pub(crate) generate: fn(&mut dyn Write, u64) -> io::Result<()>,
+    // Named implementations that should all give the same answer.
+    pub(crate) contenders: &'static [(&'static str, Solver)],
+}
+
+// xorshift64*: generated inputs only need to be varied and reproducible, not statistically strong,
+// and a fixed seed makes every run of a benchmark see the same input.
+pub(crate) struct Rng(u64);
+
+impl Rng {
+    pub(crate) fn new(seed: u64) -> Self {
+        // The state must never be zero.
+        Self(seed.max(1))
+    }
+
+    pub(crate) fn next_u64(&mut self) -> u64 {
+        self.0 ^= self.0 >> 12;
+        self.0 ^= self.0 << 25;
+        self.0 ^= self.0 >> 27;
+        self.0.wrapping_mul(0x2545_f491_4f6c_dd1d)
+    }
+
+    // Uniform enough in 0..n for the small 'n' used by the generators.
+    pub(crate) fn below(&mut self, n: u64) -> u64 {
+        self.next_u64() % n
+    }
+}
+
+pub(crate) struct BenchReport {
+    pub(crate) name: &'static str,
+    pub(crate) answer: String,
+    pub(crate) elapsed: Duration,
+    pub(crate) memory: Option<AllocStats>,
+}
+
+// Removes the generated input when the benchmark is done, even if a contender fails.
+struct GeneratedInput {
+    path: PathBuf,
+    keep: bool,
+}
+
+impl Drop for GeneratedInput {
+    fn drop(&mut self) {
+        if !self.keep {
+            let _ = fs::remove_file(&self.path);
+        }
+    }
+}
+
+// Generates an input of 'size' units (in a temporary file, or at 'keep' where it is left afterwards)
+// and runs every contender on it. Returns the input's size in bytes along with the reports.
+pub(crate) fn run(
+    day: &Day,
+    benchmark: &Benchmark,
+    size: u64,
+    keep: Option<&Path>,
+) -> Result<(u64, Vec<BenchReport>), Box<dyn error::Error>> {
+    let input = GeneratedInput {
+        path: match keep {
+            Some(path) => path.to_path_buf(),
+            None => env::temp_dir().join(format!("adventofcode-day{:02}-bench.txt", day.number)),
+        },
+        keep: keep.is_some(),
+    };
+    {
+        let _generate = tracing::info_span!("generate", size, unit = benchmark.unit).entered();
+        let mut output = BufWriter::new(File::create(&input.path)?);
+        (benchmark.generate)(&mut output, size)?;
+        output.flush()?;
+    }
+    let bytes = fs::metadata(&input.path)?.len();
+    let path = input.path.to_string_lossy().into_owned();
+
+    let mut reports = Vec::new();
+    for (name, contender) in benchmark.contenders {
+        let _contender = tracing::info_span!("contender", name).entered();
+        let start = Instant::now();
+        let (answer, memory) = memory::measure(|| contender(&path));
+        let elapsed = start.elapsed();
+        reports.push(BenchReport {
+            name,
+            answer: answer.map_err(|e| format!("{name}: {e}"))?,
+            elapsed,
+            memory,
+        });
+    }
+    Ok((bytes, reports))
+}
+
+pub(crate) fn print_table(bytes: u64, reports: &[BenchReport]) {
+    println!("Input: {:.1} MB", bytes as f64 / 1e6);
+    println!(
+        "{:<24} {:<20} {:>12} {:>10} {:>14}",
+        "Implementation", "Answer", "Time", "MB/s", "Peak (B)"
+    );
+    for report in reports {
+        let throughput = bytes as f64 / 1e6 / report.elapsed.as_secs_f64();
+        let peak = report
+            .memory
+            .map_or(String::from("-"), |stats| stats.peak_bytes.to_string());
+        println!(
+            "{:<24} {:<20} {:>12} {:>10.1} {:>14}",
+            report.name,
+            report.answer,
+            format!("{:.2?}", report.elapsed),
+            throughput,
+            peak
+        );
+    }
+    if reports
+        .windows(2)
+        .any(|pair| pair[0].answer != pair[1].answer)
+    {
+        println!("The implementations disagree!");
+    }
+}
@@ -0,0 +1,116 @@
+use std::error;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::memory::AllocStats;
+
+pub(crate) const DEFAULT_PATH: &str = ".cache/answers.json";
+
+// One cached answer. An entry is only served when the day's current solution version
+// and the hash of the input it was computed from both still match.
+#[derive(Clone, serde::Serialize, serde::Deserialize)]
+pub(crate) struct Entry {
+    pub(crate) day: u8,
+    pub(crate) part: u8,
+    pub(crate) version: u32,
+    pub(crate) input_hash: String,
+    pub(crate) answer: String,
+    pub(crate) seconds: f64,
+    pub(crate) memory: Option<AllocStats>,
+}
+
+pub(crate) struct Cache {
+    path: PathBuf,
+    entries: Vec<Entry>,
+}
+
+impl Cache {
+    // A missing cache file is simply an empty cache; an unreadable one is discarded with a warning
+    // rather than failing the run, since everything in it can be recomputed.
+    pub(crate) fn load(path: &Path) -> Self {
+        let entries = match fs::read_to_string(path) {
+            Ok(contents) => serde_json::from_str(&contents).unwrap_or_else(|e| {
+                tracing::warn!(path = %path.display(), error = %e, "discarding unreadable cache");
+                Vec::new()
+            }),
+            Err(_) => Vec::new(),
+        };
+        Self {
+            path: path.to_path_buf(),
+            entries,
+        }
+    }
+
+    pub(crate) fn get(&self, day: u8, part: u8, version: u32, input_hash: &str) -> Option<&Entry> {
+        self.entries.iter().find(|entry| {
+            entry.day == day
+                && entry.part == part
+                && entry.version == version
+                && entry.input_hash == input_hash
+        })
+    }
+
+    // Entries computed by an older (or newer) version of the same day are dropped on insert,
+    // so bumping a day's version invalidates all of its cached answers.
+    pub(crate) fn insert(&mut self, entry: Entry) {
+        self.entries.retain(|old| {
+            old.day != entry.day
+                || (old.version == entry.version
+                    && !(old.part == entry.part && old.input_hash == entry.input_hash))
+        });
+        self.entries.push(entry);
+    }
+
+    pub(crate) fn save(&self) -> Result<(), Box<dyn error::Error>> {
+        if let Some(directory) = self.path.parent() {
+            fs::create_dir_all(directory)?;
+        }
+        fs::write(&self.path, serde_json::to_string_pretty(&self.entries)?)?;
+        Ok(())
+    }
+}
+
+// 64-bit FNV-1a. 'DefaultHasher' is not guaranteed to be stable across Rust releases,
+// which would silently invalidate the cache after a toolchain update.
+pub(crate) fn hash_input(bytes: &[u8]) -> String {
+    let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+    for byte in bytes {
+        hash ^= u64::from(*byte);
+        hash = hash.wrapping_mul(0x0100_0000_01b3);
+    }
+    format!("{hash:016x}")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(part: u8, version: u32, input_hash: &str) -> Entry {
+        Entry {
+            day: 1,
+            part,
+            version,
+            input_hash: String::from(input_hash),
+            answer: String::from("42"),
+            seconds: 0.0,
+            memory: None,
+        }
+    }
+
+    #[test]
+    fn test_version_bump_invalidates_day() {
+        let mut cache = Cache {
+            path: PathBuf::new(),
+            entries: Vec::new(),
+        };
+        cache.insert(entry(1, 1, "a"));
+        cache.insert(entry(2, 1, "a"));
+        assert!(cache.get(1, 1, 1, "a").is_some());
+        assert!(cache.get(1, 1, 1, "b").is_none());
+
+        cache.insert(entry(1, 2, "a"));
+        assert!(cache.get(1, 1, 2, "a").is_some());
+        assert!(cache.get(1, 2, 1, "a").is_none());
+        assert_eq!(cache.entries.len(), 1);
+    }
+}
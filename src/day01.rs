@@ -1,68 +1,913 @@
 // cargo clippy; rustfmt src/day01.rs
 
-use std::fs;
-use std::fs::File;
-use std::io::{BufRead, BufReader};
+use std::cmp::Reverse;
+use std::collections::{BTreeMap, BinaryHeap};
+use std::error;
+use std::fmt;
+use std::io::{self, BufRead, Write};
 
-// &str is an immutable reference to a string slice.
-// Using string slice (&str) as a parameter allows the use of both &String and &str values in the method.
-pub fn part1(file_path: &str) -> u32 {
-    let puzzle_input = fs::read_to_string(file_path).unwrap();
-    let puzzle_input_chunks = puzzle_input.split("\n\n");
-
-    let mut calorie_totals: Vec<u32> = Vec::new();
-    for chunk in puzzle_input_chunks {
-        // Type annotation is required here because type inference is complex.
-        // https://rustc-dev-guide.rust-lang.org/type-inference.html
-        let inventory: Vec<u32> = chunk
-            .lines()
-            .map(|line| {
-                // ::<u32> is a type argument that is passed to the type parameter of the generic parse() method.
-                line.parse::<u32>().unwrap()
+use crate::arith::{Accumulator, OverflowError};
+use crate::bench::Rng;
+use crate::input;
+use crate::parse;
+use crate::search::{self, Options};
+
+pub(crate) struct Elf {
+    // The elf's position in the input, counting from 1.
+    pub(crate) number: usize,
+    pub(crate) items: Vec<u64>,
+    pub(crate) total: u64,
+}
+
+// Every elf's items in input order. Every blank line ends an elf, so each blank line after the
+// first in a run (and each one before the first item) stands for an elf with no items, as splitting
+// the input on "\n\n" would give. Blank lines at the very end of the input do not add elves.
+// 'stream_top_k' follows the same rule.
+pub(crate) struct Inventory {
+    pub(crate) elves: Vec<Elf>,
+}
+
+impl Inventory {
+    #[tracing::instrument(name = "parse")]
+    pub(crate) fn load(file_path: &str) -> Result<Self, Box<dyn error::Error>> {
+        Self::read(input::reader(file_path)?)
+    }
+
+    fn read(reader: impl BufRead) -> Result<Self, Box<dyn error::Error>> {
+        let mut elves = Vec::new();
+        let mut items = Vec::new();
+        let mut current_sum = Accumulator::new("calorie sum");
+        // Empty elves are only added once an item follows them, since trailing blank lines add none.
+        let mut pending_empty = 0;
+
+        for (idx, result) in reader.lines().enumerate() {
+            match parse::group_item(&result?).map_err(|e| e.at_line(idx + 1))? {
+                Some(calories) => {
+                    for _ in 0..std::mem::take(&mut pending_empty) {
+                        elves.push(Elf {
+                            number: elves.len() + 1,
+                            items: Vec::new(),
+                            total: 0,
+                        });
+                    }
+                    current_sum.add(calories, Some(idx + 1))?;
+                    items.push(calories);
+                }
+                None if items.is_empty() => pending_empty += 1,
+                None => {
+                    tracing::trace!(current_sum = current_sum.total(), "elf inventory");
+                    elves.push(Elf {
+                        number: elves.len() + 1,
+                        // 'take' moves the items out and leaves an empty vector behind for the next elf.
+                        items: std::mem::take(&mut items),
+                        total: current_sum.total(),
+                    });
+                    current_sum.reset();
+                }
+            }
+        }
+        if !items.is_empty() {
+            tracing::trace!(current_sum = current_sum.total(), "elf inventory");
+            elves.push(Elf {
+                number: elves.len() + 1,
+                items,
+                total: current_sum.total(),
+            });
+        }
+        Ok(Self { elves })
+    }
+
+    // The 'k' elves carrying the most calories, most first; ties go to the elf earlier in the input.
+    pub(crate) fn top_k(&self, k: usize) -> Vec<&Elf> {
+        let keys = self
+            .elves
+            .iter()
+            .map(|elf| (elf.total, Reverse(elf.number)));
+        top_k(keys, k)
+            .into_iter()
+            .map(|(_, Reverse(number))| &self.elves[number - 1])
+            .collect()
+    }
+
+    fn top_k_total(&self, k: usize, accumulator: &'static str) -> Result<u64, OverflowError> {
+        let mut total = Accumulator::new(accumulator);
+        for elf in self.top_k(k) {
+            total.add(elf.total, None)?;
+        }
+        Ok(total.total())
+    }
+}
+
+// The number of histogram buckets between the lightest and heaviest elf, and the longest bar.
+const HISTOGRAM_BUCKETS: usize = 10;
+const HISTOGRAM_WIDTH: usize = 40;
+
+// Summary of how calories are spread across the elves.
+pub(crate) struct Statistics {
+    pub(crate) totals: Vec<(usize, u64)>,
+    pub(crate) mean: f64,
+    pub(crate) median: f64,
+    // Nearest-rank percentiles, as (percentile, total).
+    pub(crate) percentiles: Vec<(u8, u64)>,
+    pub(crate) standard_deviation: f64,
+    // How many elves carry each number of items.
+    pub(crate) item_counts: BTreeMap<usize, usize>,
+    pub(crate) empty_groups: Vec<usize>,
+    // Totals carried by more than one elf, with those elves' numbers.
+    pub(crate) duplicated_totals: BTreeMap<u64, Vec<usize>>,
+    pub(crate) k: usize,
+    // The elves sharing the total at rank 'k' when that tie crosses the top-'k' boundary,
+    // in which case which elves count as the top 'k' depends on the tie-break.
+    pub(crate) boundary_tie: Option<Vec<usize>>,
+}
+
+impl Statistics {
+    // Buckets as inclusive (first, last, count) ranges, so the last bucket can end at 'u64::MAX'.
+    fn histogram(&self) -> Vec<(u64, u64, usize)> {
+        let lightest = self
+            .totals
+            .iter()
+            .map(|(_, total)| *total)
+            .min()
+            .unwrap_or(0);
+        let heaviest = self
+            .totals
+            .iter()
+            .map(|(_, total)| *total)
+            .max()
+            .unwrap_or(0);
+        // Rounded up, so the buckets never number more than 'HISTOGRAM_BUCKETS'.
+        let bucket_width = (heaviest - lightest) / HISTOGRAM_BUCKETS as u64 + 1;
+        let mut buckets = Vec::new();
+        let mut first = lightest;
+        loop {
+            let last = first.saturating_add(bucket_width - 1).min(heaviest);
+            let count = self
+                .totals
+                .iter()
+                .filter(|(_, total)| (first..=last).contains(total))
+                .count();
+            buckets.push((first, last, count));
+            if last == heaviest {
+                break;
+            }
+            first = last + 1;
+        }
+        buckets
+    }
+}
+
+impl fmt::Display for Statistics {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "Elves: {}", self.totals.len())?;
+        for (elf, total) in &self.totals {
+            writeln!(f, "Elf {elf}: {total}")?;
+        }
+
+        writeln!(f, "\nMean: {:.1}", self.mean)?;
+        writeln!(f, "Median: {:.1}", self.median)?;
+        writeln!(f, "Standard deviation: {:.1}", self.standard_deviation)?;
+        for (percentile, total) in &self.percentiles {
+            writeln!(f, "P{percentile}: {total}")?;
+        }
+
+        writeln!(f, "\nTotals:")?;
+        let buckets = self.histogram();
+        let most = buckets
+            .iter()
+            .map(|(_, _, count)| *count)
+            .max()
+            .unwrap_or(0)
+            .max(1);
+        for (first, last, count) in buckets {
+            let bar = "#".repeat((count * HISTOGRAM_WIDTH).div_ceil(most));
+            let row = format!("{first:>8} - {last:<8} {count:>5} {bar}");
+            writeln!(f, "{}", row.trim_end())?;
+        }
+
+        writeln!(f, "\nItems per elf:")?;
+        for (items, elves) in &self.item_counts {
+            let noun = if *elves == 1 { "elf" } else { "elves" };
+            writeln!(f, "{items:>3} item(s): {elves} {noun}")?;
+        }
+
+        if !self.empty_groups.is_empty() {
+            writeln!(f, "\nElves with no items: {:?}", self.empty_groups)?;
+        }
+        for (total, elves) in &self.duplicated_totals {
+            writeln!(f, "Elves {elves:?} all carry {total} calories.")?;
+        }
+        if let Some(elves) = &self.boundary_tie {
+            writeln!(
+                f,
+                "Elves {elves:?} tie at the top-{} boundary; the earliest in the input are counted.",
+                self.k
+            )?;
+        }
+        Ok(())
+    }
+}
+
+impl Inventory {
+    // 'None' for an inventory without any elves. 'k' is the top-K size checked for boundary ties.
+    pub(crate) fn statistics(&self, k: usize) -> Option<Statistics> {
+        if self.elves.is_empty() {
+            return None;
+        }
+        let totals: Vec<(usize, u64)> = self
+            .elves
+            .iter()
+            .map(|elf| (elf.number, elf.total))
+            .collect();
+        let mut sorted: Vec<u64> = totals.iter().map(|(_, total)| *total).collect();
+        sorted.sort_unstable();
+        let n = sorted.len();
+
+        // f64 loses precision above 2^53, which is far beyond any realistic calorie count.
+        let mean = sorted.iter().map(|total| *total as f64).sum::<f64>() / n as f64;
+        let median = if n.is_multiple_of(2) {
+            (sorted[n / 2 - 1] as f64 + sorted[n / 2] as f64) / 2.0
+        } else {
+            sorted[n / 2] as f64
+        };
+        let variance = sorted
+            .iter()
+            .map(|total| (*total as f64 - mean).powi(2))
+            .sum::<f64>()
+            / n as f64;
+        let percentiles = [10, 25, 75, 90, 99]
+            .into_iter()
+            .map(|percentile| {
+                let rank = (usize::from(percentile) * n).div_ceil(100).max(1);
+                (percentile, sorted[rank - 1])
             })
             .collect();
-        // Type annotation is *not* required here because the compiler can infer the type from the variable's usage.
-        let total_calories = inventory.iter().sum();
-        calorie_totals.push(total_calories);
+
+        let mut item_counts = BTreeMap::new();
+        let mut by_total: BTreeMap<u64, Vec<usize>> = BTreeMap::new();
+        for elf in &self.elves {
+            *item_counts.entry(elf.items.len()).or_default() += 1;
+            by_total.entry(elf.total).or_default().push(elf.number);
+        }
+        let empty_groups = self
+            .elves
+            .iter()
+            .filter(|elf| elf.items.is_empty())
+            .map(|elf| elf.number)
+            .collect();
+
+        let top = self.top_k(k + 1);
+        let boundary_tie = (k > 0 && top.len() > k && top[k - 1].total == top[k].total)
+            .then(|| by_total[&top[k].total].clone());
+        by_total.retain(|_, elves| elves.len() > 1);
+
+        Some(Statistics {
+            totals,
+            mean,
+            median,
+            percentiles,
+            standard_deviation: variance.sqrt(),
+            item_counts,
+            empty_groups,
+            duplicated_totals: by_total,
+            k,
+            boundary_tie,
+        })
+    }
+}
+
+// The two rebalancing targets: nobody above a calorie cap, or the heaviest and lightest elf
+// less than a threshold apart.
+#[derive(Clone, Copy)]
+pub(crate) enum Goal {
+    Cap(u64),
+    Spread(u64),
+}
+
+#[derive(Debug, PartialEq)]
+pub(crate) struct Transfer {
+    pub(crate) calories: u64,
+    pub(crate) from: usize,
+    pub(crate) to: usize,
+}
+
+pub(crate) struct Plan {
+    pub(crate) transfers: Vec<Transfer>,
+    // Whether no shorter plan exists. Large spread problems fall back to a greedy plan that may not be.
+    pub(crate) minimal: bool,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub(crate) enum RebalanceError {
+    #[error("Elf {elf} carries an item of {calories} calories, above the cap of {cap}.")]
+    ItemAboveCap { elf: usize, calories: u64, cap: u64 },
+    #[error("No arrangement of the items keeps every elf at or below {cap} calories.")]
+    OverCapacity { cap: u64 },
+    #[error("No item transfers bring the spread below {threshold} calories.")]
+    SpreadUnreachable { threshold: u64 },
+    // The greedy strategy got stuck and the problem is too large to search exhaustively,
+    // so a plan may still exist.
+    #[error("The greedy strategy found no plan; the inventory is too large to search for one.")]
+    NoPlanFound,
+}
+
+// Above this many elves, or after this many expansions, an exact search would take far too long.
+const EXACT_ELVES: usize = 6;
+const EXACT_EXPANSIONS: usize = 200_000;
+
+fn spread(loads: &[Vec<u64>]) -> u64 {
+    let totals = loads.iter().map(|items| items.iter().sum::<u64>());
+    totals.clone().max().unwrap_or(0) - totals.min().unwrap_or(0)
+}
+
+fn within_cap(loads: &[Vec<u64>], cap: u64) -> bool {
+    loads.iter().all(|items| items.iter().sum::<u64>() <= cap)
+}
+
+impl Inventory {
+    // Each elf's items, largest first.
+    fn loads(&self) -> Vec<Vec<u64>> {
+        self.elves
+            .iter()
+            .map(|elf| {
+                let mut items = elf.items.clone();
+                items.sort_unstable_by(|a, b| b.cmp(a));
+                items
+            })
+            .collect()
+    }
+
+    // Finds item transfers, each moving one item from one elf to another, that reach 'goal'.
+    pub(crate) fn rebalance(&self, goal: Goal) -> Result<Plan, RebalanceError> {
+        match goal {
+            Goal::Cap(cap) => self.rebalance_cap(cap),
+            Goal::Spread(threshold) => self.rebalance_spread(threshold),
+        }
+    }
+
+    // An overloaded elf has to give away at least as many items as it takes to get under the cap
+    // when giving its largest items first, so the sum of those counts is a lower bound.
+    // The greedy plan gives away exactly those items, each to the elf with the most room left,
+    // so whenever they all fit the plan meets the bound and is minimal.
+    // When they don't fit, giving away smaller items might still work (elves [6, 5] and [5]
+    // with a cap of 10), so small inventories are searched exactly instead.
+    fn rebalance_cap(&self, cap: u64) -> Result<Plan, RebalanceError> {
+        let loads = self.loads();
+        for (elf, items) in loads.iter().enumerate() {
+            if let Some(&calories) = items.first().filter(|calories| **calories > cap) {
+                return Err(RebalanceError::ItemAboveCap {
+                    elf: elf + 1,
+                    calories,
+                    cap,
+                });
+            }
+        }
+        let calories: u64 = loads.iter().flatten().sum();
+        if calories > cap.saturating_mul(loads.len() as u64) {
+            return Err(RebalanceError::OverCapacity { cap });
+        }
+        if let Some(plan) = greedy_cap(loads.clone(), cap) {
+            return Ok(plan);
+        }
+        if loads.len() > EXACT_ELVES {
+            return Err(RebalanceError::NoPlanFound);
+        }
+        match exact_search(&loads, |loads| within_cap(loads, cap)) {
+            Search::Found(plan) => Ok(plan),
+            Search::Unreachable => Err(RebalanceError::OverCapacity { cap }),
+            Search::GaveUp => Err(RebalanceError::NoPlanFound),
+        }
+    }
+
+    fn rebalance_spread(&self, threshold: u64) -> Result<Plan, RebalanceError> {
+        let loads = self.loads();
+        if spread(&loads) < threshold {
+            return Ok(Plan {
+                transfers: Vec::new(),
+                minimal: true,
+            });
+        }
+        if loads.len() <= EXACT_ELVES {
+            match exact_search(&loads, |loads| spread(loads) < threshold) {
+                Search::Found(plan) => return Ok(plan),
+                Search::Unreachable => return Err(RebalanceError::SpreadUnreachable { threshold }),
+                Search::GaveUp => {}
+            }
+        }
+        greedy_spread(loads, threshold)
     }
-    // u32 values are immutable which means a copy of the derefenced maximum value is returned.
-    return *calorie_totals.iter().max().unwrap();
 }
 
-pub fn part2(file_path: &str) -> u32 {
-    let file = File::open(file_path).unwrap();
-    let reader = BufReader::new(file);
+// 'None' when some item given away fits nowhere.
+fn greedy_cap(mut loads: Vec<Vec<u64>>, cap: u64) -> Option<Plan> {
+    let mut totals: Vec<u64> = loads.iter().map(|items| items.iter().sum()).collect();
+
+    let mut given_away = Vec::new();
+    for (elf, items) in loads.iter_mut().enumerate() {
+        while totals[elf] > cap {
+            // Largest first: the loads are sorted in descending order.
+            let calories = items.remove(0);
+            totals[elf] -= calories;
+            given_away.push((calories, elf));
+        }
+    }
+    given_away.sort_unstable_by(|a, b| b.cmp(a));
+
+    let mut transfers = Vec::new();
+    for (calories, from) in given_away {
+        let to = (0..totals.len())
+            .filter(|&to| to != from && totals[to] + calories <= cap)
+            .min_by_key(|&to| totals[to])?;
+        totals[to] += calories;
+        transfers.push(Transfer {
+            calories,
+            from: from + 1,
+            to: to + 1,
+        });
+    }
+    Some(Plan {
+        transfers,
+        minimal: true,
+    })
+}
 
-    let mut highest_sums = [0, 0, 0];
-    let mut current_sum = 0;
+enum Search {
+    Found(Plan),
+    // Every arrangement reachable by moving items was tried.
+    Unreachable,
+    // The search ran out of expansions first.
+    GaveUp,
+}
 
-    for result in reader.lines() {
-        let line = result.unwrap();
-        if line.is_empty() {
-            replace_min(&mut highest_sums, current_sum);
-            current_sum = 0;
+// A breadth-first search over every way of moving one item, so the first plan found is the shortest.
+fn exact_search(loads: &[Vec<u64>], is_goal: impl FnMut(&Vec<Vec<u64>>) -> bool) -> Search {
+    // 'search::bfs' returns 'None' both when it gives up and when it runs out of states;
+    // it only gives up after expanding 'EXACT_EXPANSIONS' states, so fewer means it ran out.
+    let mut expanded = 0;
+    let neighbours = |loads: &Vec<Vec<u64>>| {
+        expanded += 1;
+        let mut next = Vec::new();
+        for from in 0..loads.len() {
+            let mut items = loads[from].clone();
+            // Moving either of two equal items gives the same state.
+            items.dedup();
+            for calories in items {
+                for to in (0..loads.len()).filter(|to| *to != from) {
+                    let mut moved = loads.clone();
+                    let position = moved[from].partition_point(|item| *item > calories);
+                    moved[from].remove(position);
+                    let position = moved[to].partition_point(|item| *item > calories);
+                    moved[to].insert(position, calories);
+                    next.push(moved);
+                }
+            }
+        }
+        next
+    };
+    let options = Options {
+        max_expansions: Some(EXACT_EXPANSIONS),
+        ..Options::default()
+    };
+    let Some(path) = search::bfs(loads.to_vec(), neighbours, is_goal, options) else {
+        return if expanded < EXACT_EXPANSIONS {
+            Search::Unreachable
         } else {
-            current_sum += line.parse::<u32>().unwrap();
+            Search::GaveUp
+        };
+    };
+    let transfers = path
+        .states
+        .windows(2)
+        .map(|step| {
+            let (before, after) = (&step[0], &step[1]);
+            let from = (0..before.len()).find(|&i| after[i].len() < before[i].len());
+            let to = (0..before.len()).find(|&i| after[i].len() > before[i].len());
+            let (from, to) = from.zip(to).expect("each step moves one item");
+            let calories = before[from].iter().sum::<u64>() - after[from].iter().sum::<u64>();
+            Transfer {
+                calories,
+                from: from + 1,
+                to: to + 1,
+            }
+        })
+        .collect();
+    Search::Found(Plan {
+        transfers,
+        minimal: true,
+    })
+}
+
+// Repeatedly moves the item from the heaviest elf to the lightest that brings the two closest.
+// Only items between zero and the gap between them are moved, which always lowers the sum of squared
+// totals, so this cannot loop forever. Getting stuck proves nothing, since moving an item elsewhere
+// (or first making room) might still work.
+fn greedy_spread(mut loads: Vec<Vec<u64>>, threshold: u64) -> Result<Plan, RebalanceError> {
+    let mut transfers = Vec::new();
+    loop {
+        let totals: Vec<u64> = loads.iter().map(|items| items.iter().sum()).collect();
+        let heaviest = (0..totals.len()).max_by_key(|&i| totals[i]).unwrap_or(0);
+        let lightest = (0..totals.len()).min_by_key(|&i| totals[i]).unwrap_or(0);
+        let gap = totals[heaviest] - totals[lightest];
+        if gap < threshold {
+            return Ok(Plan {
+                transfers,
+                minimal: false,
+            });
+        }
+        let position = (0..loads[heaviest].len())
+            .filter(|&i| 0 < loads[heaviest][i] && loads[heaviest][i] < gap)
+            .min_by_key(|&i| gap.abs_diff(2 * loads[heaviest][i]))
+            .ok_or(RebalanceError::NoPlanFound)?;
+        let calories = loads[heaviest].remove(position);
+        let position = loads[lightest].partition_point(|item| *item > calories);
+        loads[lightest].insert(position, calories);
+        transfers.push(Transfer {
+            calories,
+            from: heaviest + 1,
+            to: lightest + 1,
+        });
+    }
+}
+
+// A min-heap holding at most 'k' values: once it is full, each new value pushes out the smallest.
+// This generalises a fixed array of the three highest values to any 'k' in O(n log k) time
+// and O(k) memory, however many values are pushed.
+pub(crate) struct TopK<T> {
+    k: usize,
+    heap: BinaryHeap<Reverse<T>>,
+}
+
+impl<T: Ord> TopK<T> {
+    pub(crate) fn new(k: usize) -> Self {
+        // Capped, since 'k' may be far larger than the number of values ever pushed ('--top' takes any 'usize').
+        Self {
+            k,
+            heap: BinaryHeap::with_capacity(k.saturating_add(1).min(1024)),
+        }
+    }
+
+    pub(crate) fn push(&mut self, value: T) {
+        // 'Reverse' flips the ordering, turning Rust's max-heap into a min-heap.
+        self.heap.push(Reverse(value));
+        if self.heap.len() > self.k {
+            self.heap.pop();
+        }
+    }
+
+    // Largest first.
+    pub(crate) fn into_sorted_vec(self) -> Vec<T> {
+        // A min-heap of 'Reverse' values sorts ascending by 'Reverse', which is descending by value.
+        self.heap
+            .into_sorted_vec()
+            .into_iter()
+            .map(|Reverse(value)| value)
+            .collect()
+    }
+}
+
+pub(crate) fn top_k<T: Ord>(values: impl IntoIterator<Item = T>, k: usize) -> Vec<T> {
+    let mut top = TopK::new(k);
+    for value in values {
+        top.push(value);
+    }
+    top.into_sorted_vec()
+}
+
+// The 'k' highest elf totals, most first, in a single pass over 'reader'. Only the current elf's
+// running sum, the 'k' best totals and one reused line buffer are kept, so memory stays constant
+// however large the input is. Blank lines separate elves as in 'Inventory' (extra blank lines are
+// elves with no items, trailing ones are not elves), and the last elf counts whether or not the input
+// ends with a blank line.
+pub(crate) fn stream_top_k(
+    mut reader: impl BufRead,
+    k: usize,
+) -> Result<Vec<u64>, Box<dyn error::Error>> {
+    let mut top = TopK::new(k);
+    let mut current_sum = Accumulator::new("calorie sum");
+    let mut in_group = false;
+    let mut pending_empty = 0;
+    let mut line = String::new();
+    let mut line_number = 0;
+
+    loop {
+        line.clear();
+        if reader.read_line(&mut line)? == 0 {
+            break;
+        }
+        line_number += 1;
+        let item = line.trim_end_matches(['\n', '\r']);
+        match parse::group_item(item).map_err(|e| e.at_line(line_number))? {
+            Some(calories) => {
+                for _ in 0..std::mem::take(&mut pending_empty) {
+                    top.push(0);
+                }
+                current_sum.add(calories, Some(line_number))?;
+                in_group = true;
+            }
+            None if in_group => {
+                tracing::trace!(current_sum = current_sum.total(), "elf inventory");
+                top.push(current_sum.total());
+                current_sum.reset();
+                in_group = false;
+            }
+            None => pending_empty += 1,
+        }
+    }
+    if in_group {
+        tracing::trace!(current_sum = current_sum.total(), "elf inventory");
+        top.push(current_sum.total());
+    }
+    Ok(top.into_sorted_vec())
+}
+
+// Items are parsed as they are ranked, so one 'solve' span covers the whole stream.
+fn sum_top_k(
+    file_path: &str,
+    k: usize,
+    accumulator: &'static str,
+) -> Result<u64, Box<dyn error::Error>> {
+    let _solve = tracing::info_span!("solve").entered();
+    let mut total = Accumulator::new(accumulator);
+    for sum in stream_top_k(input::reader(file_path)?, k)? {
+        total.add(sum, None)?;
+    }
+    Ok(total.total())
+}
+
+// &str is an immutable reference to a string slice.
+// Using string slice (&str) as a parameter allows the use of both &String and &str values in the method.
+#[tracing::instrument]
+pub fn part1(file_path: &str) -> Result<u64, Box<dyn error::Error>> {
+    sum_top_k(file_path, 1, "top total")
+}
+
+#[tracing::instrument]
+pub fn part2(file_path: &str) -> Result<u64, Box<dyn error::Error>> {
+    sum_top_k(file_path, 3, "top three total")
+}
+
+// The previous part 2, which parses every elf's items before ranking them; kept to benchmark against.
+pub fn part2_inventory(file_path: &str) -> Result<u64, Box<dyn error::Error>> {
+    Ok(Inventory::load(file_path)?.top_k_total(3, "top three total")?)
+}
+
+// Writes roughly 'megabytes' MB of calorie lists: elves of 1 to 15 items of 1000 to 59999 calories.
+pub fn generate(output: &mut dyn Write, megabytes: u64) -> io::Result<()> {
+    let mut rng = Rng::new(1);
+    let limit = megabytes * 1_000_000;
+    let mut written = 0;
+    while written < limit {
+        for _ in 0..=rng.below(15) {
+            let calories = 1000 + rng.below(59_000);
+            writeln!(output, "{calories}")?;
+            written += if calories >= 10_000 { 6 } else { 5 };
         }
+        writeln!(output)?;
+        written += 1;
     }
+    Ok(())
+}
+
+// The 'k' elves carrying the most calories as a table, for 'run --day 1 --top K'.
+pub fn top(file_path: &str, k: usize) -> Result<String, Box<dyn error::Error>> {
+    let inventory = Inventory::load(file_path)?;
+    let mut table = format!(
+        "{:<5} {:<6} {:>6} {:>12}\n",
+        "Rank", "Elf", "Items", "Calories"
+    );
+    for (rank, elf) in inventory.top_k(k).iter().enumerate() {
+        table.push_str(&format!(
+            "{:<5} {:<6} {:>6} {:>12}\n",
+            rank + 1,
+            elf.number,
+            elf.items.len(),
+            elf.total
+        ));
+    }
+    let total = inventory.top_k_total(k, "top k total")?;
+    table.push_str(&format!("{:<5} {:<6} {:>6} {:>12}", "Total", "", "", total));
+    Ok(table)
+}
+
+// A rebalancing plan as text, for 'run --day 1 --cap N' and '--spread N'.
+pub fn rebalance(file_path: &str, goal: Goal) -> Result<String, Box<dyn error::Error>> {
+    let plan = Inventory::load(file_path)?.rebalance(goal)?;
+    let mut text = String::new();
+    for transfer in &plan.transfers {
+        text.push_str(&format!(
+            "move {} calories from elf {} to elf {}\n",
+            transfer.calories, transfer.from, transfer.to
+        ));
+    }
+    let note = if plan.minimal {
+        "minimal"
+    } else {
+        "greedy, may not be minimal"
+    };
+    text.push_str(&format!("{} transfer(s) ({note})", plan.transfers.len()));
+    Ok(text)
+}
 
-    // Passing a mutable *reference* to the argument slice is called a mutable borrow.
-    replace_min(&mut highest_sums, current_sum);
-    return highest_sums.iter().sum::<u32>();
+pub fn explain(file_path: &str) -> Result<String, Box<dyn error::Error>> {
+    let statistics = Inventory::load(file_path)?
+        .statistics(3)
+        .ok_or("The inventory has no elves.")?;
+    Ok(statistics.to_string())
 }
 
-// Using a slice reference &[u32] instead of a vector reference &Vec<u32> in the type definition.
-// &Vec<u32> as an argument type would require a vector be allocated on the heap prior to calling the function.
-// &Vec<u32> will be automatically coerced into a &[u32].
-fn replace_min(vec: &mut [u32], value: u32) {
-    let min_index = vec
-        .iter()
-        .enumerate()
-        .min_by(|(_, a), (_, b)| a.cmp(b))
-        .map(|(i, _)| i)
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_top_k() {
+        let totals = [6000, 4000, 11000, 24000, 10000];
+        assert_eq!(top_k(totals, 1), [24000]);
+        assert_eq!(top_k(totals, 3), [24000, 11000, 10000]);
+        assert_eq!(top_k(totals, 9), [24000, 11000, 10000, 6000, 4000]);
+        assert!(top_k(totals, 0).is_empty());
+        assert_eq!(top_k(totals, usize::MAX).len(), 5);
+    }
+
+    #[test]
+    fn test_stream_top_k() {
+        let example = "1000\n2000\n3000\n\n4000\n\n5000\n6000\n\n7000\n8000\n9000\n\n10000\n";
+        assert_eq!(
+            stream_top_k(example.as_bytes(), 3).unwrap(),
+            [24000, 11000, 10000]
+        );
+
+        // Blank line runs, CRLF line endings and no trailing newline. Both paths see two empty elves
+        // before the first item and two more between the groups.
+        let messy = "\n\n1000\r\n\r\n\r\n\n2000\n3000";
+        assert_eq!(stream_top_k(messy.as_bytes(), 3).unwrap(), [5000, 1000, 0]);
+        let inventory = Inventory::read(messy.as_bytes()).unwrap();
+        let totals: Vec<u64> = inventory.elves.iter().map(|elf| elf.total).collect();
+        assert_eq!(totals, [0, 0, 1000, 0, 0, 5000]);
+        assert_eq!(stream_top_k(messy.as_bytes(), 9).unwrap().len(), 6);
+        // Trailing blank lines are not elves.
+        let trailing = "1000\n\n\n\n";
+        assert_eq!(Inventory::read(trailing.as_bytes()).unwrap().elves.len(), 1);
+        assert_eq!(stream_top_k(trailing.as_bytes(), 9).unwrap(), [1000]);
+        let huge = format!("{}\n{}\n", u64::from(u32::MAX), u64::from(u32::MAX));
+        assert_eq!(
+            stream_top_k(huge.as_bytes(), 1).unwrap(),
+            [2 * u64::from(u32::MAX)]
+        );
+    }
+
+    #[test]
+    fn test_statistics() {
+        let statistics = inventory(&[
+            &[1000, 2000],
+            &[],
+            &[3000],
+            &[500, 500, 2000],
+            &[4000],
+            &[5000],
+        ])
+        .statistics(3)
         .unwrap();
 
-    if value > vec[min_index] {
-        vec[min_index] = value;
+        assert_eq!(statistics.mean, 3000.0);
+        assert_eq!(statistics.median, 3000.0);
+        assert_eq!(statistics.percentiles[0], (10, 0));
+        assert_eq!(statistics.empty_groups, [2]);
+        assert_eq!(statistics.item_counts[&1], 3);
+        assert_eq!(statistics.duplicated_totals[&3000], [1, 3, 4]);
+        assert_eq!(statistics.boundary_tie, Some(vec![1, 3, 4]));
+        assert!(Inventory { elves: Vec::new() }.statistics(3).is_none());
+
+        // Every elf lands in exactly one bucket, up to and including the heaviest.
+        let histogram = statistics.histogram();
+        assert_eq!(histogram.first().unwrap().0, 0);
+        assert_eq!(histogram.last().unwrap().1, 5000);
+        let counted: usize = histogram.iter().map(|(_, _, count)| count).sum();
+        assert_eq!(counted, 6);
+        assert!(histogram.len() <= HISTOGRAM_BUCKETS);
+
+        // The per-elf totals come first, in input order.
+        let text = statistics.to_string();
+        let elves: Vec<&str> = text.lines().skip(1).take(6).collect();
+        assert_eq!(
+            elves,
+            [
+                "Elf 1: 3000",
+                "Elf 2: 0",
+                "Elf 3: 3000",
+                "Elf 4: 3000",
+                "Elf 5: 4000",
+                "Elf 6: 5000"
+            ]
+        );
+        assert!(text.find("Elf 6: 5000") < text.find("P10:"));
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_histogram_reaches_u64_max() {
+        for groups in [
+            &[&[u64::MAX][..], &[0]][..],
+            &[&[u64::MAX]],
+            &[&[u64::MAX - 5], &[u64::MAX]],
+        ] {
+            let statistics = inventory(groups).statistics(1).unwrap();
+            let histogram = statistics.histogram();
+            assert_eq!(histogram.last().unwrap().1, u64::MAX);
+            let counted: usize = histogram.iter().map(|(_, _, count)| count).sum();
+            assert_eq!(counted, groups.len());
+            assert!(statistics.to_string().contains(&u64::MAX.to_string()));
+        }
+    }
+
+    fn inventory(groups: &[&[u64]]) -> Inventory {
+        let elves = groups
+            .iter()
+            .enumerate()
+            .map(|(i, items)| Elf {
+                number: i + 1,
+                items: items.to_vec(),
+                total: items.iter().sum(),
+            })
+            .collect();
+        Inventory { elves }
+    }
+
+    #[test]
+    fn test_rebalance() {
+        let example = inventory(&[
+            &[1000, 2000, 3000],
+            &[4000],
+            &[5000, 6000],
+            &[7000, 8000, 9000],
+            &[10000],
+        ]);
+
+        let plan = example.rebalance(Goal::Cap(15000)).unwrap();
+        assert!(plan.minimal);
+        assert_eq!(
+            plan.transfers,
+            [Transfer {
+                calories: 9000,
+                from: 4,
+                to: 2
+            }]
+        );
+        assert!(matches!(
+            example.rebalance(Goal::Cap(9000)),
+            Err(RebalanceError::ItemAboveCap { elf: 5, .. })
+        ));
+        assert!(matches!(
+            example.rebalance(Goal::Cap(10000)),
+            Err(RebalanceError::OverCapacity { .. })
+        ));
+
+        // Giving away the 6 leaves nowhere to put it, but giving away a 5 works.
+        let stuck = inventory(&[&[6, 5], &[5]]);
+        let plan = stuck.rebalance(Goal::Cap(10)).unwrap();
+        assert!(plan.minimal);
+        assert_eq!(
+            plan.transfers,
+            [Transfer {
+                calories: 5,
+                from: 1,
+                to: 2
+            }]
+        );
+        // The items fit in total, but no two of them fit together, so the search proves it.
+        assert!(matches!(
+            inventory(&[&[6, 5], &[6]]).rebalance(Goal::Cap(10)),
+            Err(RebalanceError::OverCapacity { .. })
+        ));
+
+        // Totals 6000, 4000, 11000, 24000, 10000: no single transfer gets them all within 5000.
+        let plan = example.rebalance(Goal::Spread(5000)).unwrap();
+        assert!(plan.minimal);
+        assert_eq!(plan.transfers.len(), 2);
+        assert_eq!(
+            example.rebalance(Goal::Spread(30000)).unwrap().transfers,
+            []
+        );
+
+        // Too many elves for the exact search, so the greedy strategy plans these. It never moves
+        // the 0, which would change nothing: here it moves the 5, and with only the 0 lighter than
+        // the gap it gives up instead of moving the 0 back and forth forever.
+        let mut groups: Vec<&[u64]> = vec![&[5, 3, 0], &[]];
+        groups.resize(EXACT_ELVES + 1, &[4]);
+        let plan = inventory(&groups).rebalance(Goal::Spread(5)).unwrap();
+        assert!(!plan.minimal);
+        assert_eq!(
+            plan.transfers,
+            [Transfer {
+                calories: 5,
+                from: 1,
+                to: 2
+            }]
+        );
+        let mut groups: Vec<&[u64]> = vec![&[10, 0]];
+        groups.resize(EXACT_ELVES + 1, &[]);
+        assert!(matches!(
+            inventory(&groups).rebalance(Goal::Spread(5)),
+            Err(RebalanceError::NoPlanFound)
+        ));
+    }
+}
@@ -1,120 +1,1256 @@
+use std::collections::BTreeMap;
+use std::error;
 use std::fs;
+use std::io::{self, BufRead, Write};
+use std::path::{Path, PathBuf};
+
+use crate::arith::Accumulator;
+use crate::bench::Rng;
+use crate::input;
+use crate::parse;
 
 const WIN: u32 = 6;
 const DRAW: u32 = 3;
 const LOSE: u32 = 0;
 
-const ROCK_VALUE: u32 = 1;
-const PAPER_VALUE: u32 = 2;
-const SCISSORS_VALUE: u32 = 3;
+// A move is its position in the game's cycle.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub(crate) struct Move(usize);
 
-#[derive(Copy, Clone, PartialEq)]
-enum RPS {
-    Rock,
-    Paper,
-    Scissors,
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, serde::Serialize)]
+#[serde(rename_all = "lowercase")]
+pub(crate) enum Outcome {
+    Lose,
+    Draw,
+    Win,
 }
 
-impl RPS {
-    fn value(&self) -> u32 {
-        match self {
-            RPS::Rock => ROCK_VALUE,
-            RPS::Paper => PAPER_VALUE,
-            RPS::Scissors => SCISSORS_VALUE,
+// The puzzle's own symbols: 'A' or 'X' is Rock, 'B' or 'Y' Paper and 'C' or 'Z' Scissors.
+// Other games go through 'Game::decode' and their own symbol tables.
+impl TryFrom<char> for Move {
+    type Error = GameError;
+
+    fn try_from(symbol: char) -> Result<Self, Self::Error> {
+        match symbol {
+            'A' | 'X' => Ok(Move(0)),
+            'B' | 'Y' => Ok(Move(1)),
+            'C' | 'Z' => Ok(Move(2)),
+            _ => Err(GameError::UnknownSymbol {
+                table: "move",
+                symbol: symbol.to_string(),
+            }),
         }
     }
+}
 
-    fn stronger(&self) -> RPS {
-        match self {
-            RPS::Rock => RPS::Paper,
-            RPS::Paper => RPS::Scissors,
-            RPS::Scissors => RPS::Rock,
+impl TryFrom<char> for Outcome {
+    type Error = GameError;
+
+    fn try_from(symbol: char) -> Result<Self, Self::Error> {
+        match symbol {
+            'X' => Ok(Outcome::Lose),
+            'Y' => Ok(Outcome::Draw),
+            'Z' => Ok(Outcome::Win),
+            _ => Err(GameError::UnknownSymbol {
+                table: "outcome",
+                symbol: symbol.to_string(),
+            }),
         }
     }
+}
+
+// How the second column of the strategy guide is read: as the player's move (part 1),
+// or as the outcome the player needs (part 2).
+#[derive(Copy, Clone, Debug, PartialEq, serde::Serialize)]
+#[serde(rename_all = "lowercase")]
+pub(crate) enum Interpretation {
+    Moves,
+    Outcomes,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub(crate) enum GameError {
+    #[error("A cyclic game needs an odd number of moves, at least three, not {0}.")]
+    MoveCount(usize),
+    #[error("Expected {expected} entries in the {table} table, found {found}.")]
+    TableSize {
+        table: &'static str,
+        expected: usize,
+        found: usize,
+    },
+    #[error("The {table} table uses the symbol {symbol} more than once.")]
+    DuplicateSymbol { table: &'static str, symbol: char },
+    #[error("Unknown {table} symbol: {symbol}.")]
+    UnknownSymbol { table: &'static str, symbol: String },
+    #[error("Cannot lose {losses} rounds of a {rounds} round guide.")]
+    Losses { losses: usize, rounds: usize },
+    #[error("A tournament needs at least two players, not {0}.")]
+    Players(usize),
+}
 
-    fn equal(&self) -> RPS {
-        *self
+// A game where the moves sit in a cycle and each beats the (n - 1) / 2 moves before it,
+// so with an odd number of moves every move beats exactly as many moves as it loses to.
+// Rock-paper-scissors is the cycle Rock, Paper, Scissors; Rock-paper-scissors-lizard-Spock
+// is Rock, Spock, Paper, Lizard, Scissors.
+#[derive(Clone)]
+pub(crate) struct Game {
+    names: Vec<String>,
+    shape_scores: Vec<u32>,
+    // Indexed by 'Outcome as usize': lose, draw, win.
+    outcome_scores: [u32; 3],
+    opponent_symbols: Vec<char>,
+    player_symbols: Vec<char>,
+    outcome_symbols: [char; 3],
+}
+
+impl Game {
+    // Shape scores default to 1, 2, 3... in cycle order, outcomes to 0, 3 and 6 points, and the
+    // symbols to 'A', 'B', 'C'... for the opponent and 'X', 'Y', 'Z' for the player and outcomes.
+    // Games of more than three moves need their own player symbols ('with_player_symbols'):
+    // until then the player table is empty, and 'check_tables' rejects the game.
+    pub(crate) fn new(names: &[&str]) -> Result<Self, GameError> {
+        let n = names.len();
+        if n < 3 || n.is_multiple_of(2) {
+            return Err(GameError::MoveCount(n));
+        }
+        Ok(Self {
+            names: names.iter().map(|name| name.to_string()).collect(),
+            shape_scores: (1..=n as u32).collect(),
+            outcome_scores: [LOSE, DRAW, WIN],
+            opponent_symbols: ('A'..).take(n).collect(),
+            player_symbols: if n == 3 {
+                vec!['X', 'Y', 'Z']
+            } else {
+                Vec::new()
+            },
+            outcome_symbols: ['X', 'Y', 'Z'],
+        })
     }
 
-    fn weaker(&self) -> RPS {
-        match self {
-            RPS::Rock => RPS::Scissors,
-            RPS::Paper => RPS::Rock,
-            RPS::Scissors => RPS::Paper,
+    pub(crate) fn rock_paper_scissors() -> Self {
+        Self::new(&["Rock", "Paper", "Scissors"]).expect("three moves make a valid game")
+    }
+
+    pub(crate) fn with_shape_scores(mut self, scores: &[u32]) -> Result<Self, GameError> {
+        check_size("shape score", self.names.len(), scores.len())?;
+        self.shape_scores = scores.to_vec();
+        Ok(self)
+    }
+
+    pub(crate) fn with_outcome_scores(mut self, lose: u32, draw: u32, win: u32) -> Self {
+        self.outcome_scores = [lose, draw, win];
+        self
+    }
+
+    pub(crate) fn with_opponent_symbols(mut self, symbols: &[char]) -> Result<Self, GameError> {
+        check_symbols("opponent symbol", self.names.len(), symbols)?;
+        self.opponent_symbols = symbols.to_vec();
+        Ok(self)
+    }
+
+    pub(crate) fn with_player_symbols(mut self, symbols: &[char]) -> Result<Self, GameError> {
+        check_symbols("player symbol", self.names.len(), symbols)?;
+        self.player_symbols = symbols.to_vec();
+        Ok(self)
+    }
+
+    pub(crate) fn with_outcome_symbols(
+        mut self,
+        lose: char,
+        draw: char,
+        win: char,
+    ) -> Result<Self, GameError> {
+        check_symbols("outcome symbol", 3, &[lose, draw, win])?;
+        self.outcome_symbols = [lose, draw, win];
+        Ok(self)
+    }
+
+    // Every symbol table has one distinct symbol per entry, so each symbol decodes to one thing.
+    pub(crate) fn check_tables(&self) -> Result<(), GameError> {
+        let n = self.names.len();
+        check_size("shape score", n, self.shape_scores.len())?;
+        check_symbols("opponent symbol", n, &self.opponent_symbols)?;
+        check_symbols("player symbol", n, &self.player_symbols)?;
+        check_symbols("outcome symbol", 3, &self.outcome_symbols)
+    }
+
+    pub(crate) fn name(&self, shape: Move) -> &str {
+        &self.names[shape.0]
+    }
+
+    // 'a' beats 'b' when 'b' is one of the (n - 1) / 2 moves before 'a' in the cycle.
+    pub(crate) fn beats(&self, a: Move, b: Move) -> bool {
+        let n = self.names.len();
+        (1..=(n - 1) / 2).contains(&((a.0 + n - b.0) % n))
+    }
+
+    pub(crate) fn outcome(&self, player: Move, opponent: Move) -> Outcome {
+        if self.beats(player, opponent) {
+            Outcome::Win
+        } else if player == opponent {
+            Outcome::Draw
+        } else {
+            Outcome::Lose
+        }
+    }
+
+    // A move giving 'outcome' against 'opponent': its neighbour in the cycle for a win or a loss,
+    // which for three moves is the only such move.
+    pub(crate) fn response(&self, opponent: Move, outcome: Outcome) -> Move {
+        let n = self.names.len();
+        match outcome {
+            Outcome::Lose => Move((opponent.0 + n - 1) % n),
+            Outcome::Draw => opponent,
+            Outcome::Win => Move((opponent.0 + 1) % n),
+        }
+    }
+
+    // The shape points and outcome points 'player' earns in one round against 'opponent'.
+    pub(crate) fn points(&self, player: Move, opponent: Move) -> (u32, u32) {
+        (
+            self.shape_scores[player.0],
+            self.outcome_scores[self.outcome(player, opponent) as usize],
+        )
+    }
+
+    // Both players' scores for one round: shape score plus outcome score.
+    pub(crate) fn play_game(&self, p1: Move, p2: Move) -> (u32, u32) {
+        let score = |player, opponent| {
+            let (shape, outcome) = self.points(player, opponent);
+            shape + outcome
+        };
+        (score(p1, p2), score(p2, p1))
+    }
+
+    // The highest-scoring move against 'opponent' among those whose outcome is 'allowed', with its score.
+    // Ties go to the move first in the cycle.
+    fn best_response(
+        &self,
+        opponent: Move,
+        allowed: impl Fn(Outcome) -> bool,
+    ) -> Option<(Move, u32)> {
+        let mut best: Option<(Move, u32)> = None;
+        for candidate in (0..self.names.len()).map(Move) {
+            if !allowed(self.outcome(candidate, opponent)) {
+                continue;
+            }
+            let (_, score) = self.play_game(opponent, candidate);
+            if best.is_none_or(|(_, best_score)| score > best_score) {
+                best = Some((candidate, score));
+            }
         }
+        best
+    }
+
+    fn lookup(
+        &self,
+        table: &'static str,
+        symbols: &[char],
+        symbol: &str,
+    ) -> Result<usize, GameError> {
+        let mut chars = symbol.chars();
+        let position = match (chars.next(), chars.next()) {
+            (Some(character), None) => symbols.iter().position(|candidate| *candidate == character),
+            _ => None,
+        };
+        position.ok_or_else(|| GameError::UnknownSymbol {
+            table,
+            symbol: String::from(symbol),
+        })
+    }
+
+    // The moves of a strategy guide round "<opponent> <player or outcome>".
+    pub(crate) fn decode(
+        &self,
+        opponent: &str,
+        second: &str,
+        interpretation: Interpretation,
+    ) -> Result<(Move, Move), GameError> {
+        let p1 = Move(self.lookup("opponent", &self.opponent_symbols, opponent)?);
+        let p2 = match interpretation {
+            Interpretation::Moves => Move(self.lookup("player", &self.player_symbols, second)?),
+            Interpretation::Outcomes => {
+                let outcome = match self.lookup("outcome", &self.outcome_symbols, second)? {
+                    0 => Outcome::Lose,
+                    1 => Outcome::Draw,
+                    _ => Outcome::Win,
+                };
+                self.response(p1, outcome)
+            }
+        };
+        Ok((p1, p2))
     }
 }
 
-pub fn part1(file_path: &str) -> u32 {
-    let puzzle_input = fs::read_to_string(file_path).unwrap();
-    let lines = puzzle_input.split('\n');
+// A game described in JSON. Only "moves" is required, e.g.
+// {"moves": ["Rock", "Spock", "Paper", "Lizard", "Scissors"], "player_symbols": ["V", "W", "X", "Y", "Z"]}
+#[derive(serde::Deserialize)]
+struct GameConfig {
+    moves: Vec<String>,
+    shape_scores: Option<Vec<u32>>,
+    // Lose, draw, win.
+    outcome_scores: Option<[u32; 3]>,
+    opponent_symbols: Option<Vec<char>>,
+    player_symbols: Option<Vec<char>>,
+    outcome_symbols: Option<[char; 3]>,
+}
 
-    let mut total = 0;
-    for line in lines {
-        let mut inputs = line.split(' ');
-        let (input1, input2) = (inputs.next().unwrap(), inputs.next().unwrap());
-        let (p1, p2) = get_player_choices(input1, input2);
-        let (_, score) = play_game(p1, p2);
-        total += score;
+impl Game {
+    pub(crate) fn load(config_path: &Path) -> Result<Self, Box<dyn error::Error>> {
+        let config: GameConfig = serde_json::from_str(&fs::read_to_string(config_path)?)?;
+        let moves: Vec<&str> = config.moves.iter().map(String::as_str).collect();
+        let mut game = Game::new(&moves)?;
+        if let Some(scores) = config.shape_scores {
+            game = game.with_shape_scores(&scores)?;
+        }
+        if let Some([lose, draw, win]) = config.outcome_scores {
+            game = game.with_outcome_scores(lose, draw, win);
+        }
+        if let Some(symbols) = config.opponent_symbols {
+            game = game.with_opponent_symbols(&symbols)?;
+        }
+        if let Some(symbols) = config.player_symbols {
+            game = game.with_player_symbols(&symbols)?;
+        }
+        if let Some([lose, draw, win]) = config.outcome_symbols {
+            game = game.with_outcome_symbols(lose, draw, win)?;
+        }
+        game.check_tables()?;
+        Ok(game)
     }
-    total
 }
 
-pub fn part2(file_path: &str) -> u32 {
-    let puzzle_input = fs::read_to_string(file_path).unwrap();
-    let lines = puzzle_input.split('\n');
+fn check_symbols(table: &'static str, expected: usize, symbols: &[char]) -> Result<(), GameError> {
+    check_size(table, expected, symbols.len())?;
+    for (i, symbol) in symbols.iter().enumerate() {
+        if symbols[..i].contains(symbol) {
+            return Err(GameError::DuplicateSymbol {
+                table,
+                symbol: *symbol,
+            });
+        }
+    }
+    Ok(())
+}
+
+fn check_size(table: &'static str, expected: usize, found: usize) -> Result<(), GameError> {
+    if expected == found {
+        Ok(())
+    } else {
+        Err(GameError::TableSize {
+            table,
+            expected,
+            found,
+        })
+    }
+}
 
-    let mut total = 0;
-    for line in lines {
-        let mut inputs = line.split(' ');
-        let (input1, input2) = (inputs.next().unwrap(), inputs.next().unwrap());
-        let (p1, p2) = get_player_choices2(input1, input2);
-        let (_, score) = play_game(p1, p2);
-        total += score;
+// Rounds are decoded as they are scored, so there is no separate parse phase: like 'guide_total',
+// one 'solve' span covers the whole loop.
+fn total_score(
+    file_path: &str,
+    game: &Game,
+    interpretation: Interpretation,
+) -> Result<u64, Box<dyn error::Error>> {
+    let _solve = tracing::info_span!("solve").entered();
+    let mut total = Accumulator::new("score total");
+    for (idx, result) in input::lines(file_path)?.enumerate() {
+        let line = result?;
+        let (input1, input2) = parse::token_pair(&line).map_err(|e| e.at_line(idx + 1))?;
+        let (p1, p2) = game.decode(input1, input2, interpretation)?;
+        let (_, score) = game.play_game(p1, p2);
+        tracing::trace!(p1 = game.name(p1), p2 = game.name(p2), score, "round");
+        total.add(u64::from(score), Some(idx + 1))?;
     }
-    total
+    Ok(total.total())
 }
 
-// An alternative is to implement the 'TryFrom' trait on 'RPS' - 'impl TryFrom<char> for RPS'.
-// 'TryFrom' instead of 'From' because this conversion is fallible, allowing for RPS::try_from(some_char)
-fn get_player_choices(input1: &str, input2: &str) -> (RPS, RPS) {
-    let p1 = match input1 {
-        "A" => RPS::Rock,
-        "B" => RPS::Paper,
-        "C" => RPS::Scissors,
-        _ => panic!("Unexpected puzzle input: {input1}"),
+// The puzzle's total for rock-paper-scissors, straight from the mapped input's bytes: every round
+// is checked with 'parse::guide_round' and scored from a table built with 'play_game' up front,
+// so nothing is allocated per round. Configured games go through the slower 'total_score'.
+// Each round's bytes are checked as it is scored, so the 'parse' span only covers mapping the guide
+// and a single 'solve' span covers the whole loop; a span per round would cost more than the round.
+fn guide_total(
+    file_path: &str,
+    interpretation: Interpretation,
+) -> Result<u64, Box<dyn error::Error>> {
+    let game = Game::rock_paper_scissors();
+    let mut scores = [[0; 3]; 3];
+    for (opponent, row) in scores.iter_mut().enumerate() {
+        for (second, score) in row.iter_mut().enumerate() {
+            let player = match interpretation {
+                Interpretation::Moves => Move(second),
+                Interpretation::Outcomes => {
+                    let outcome = [Outcome::Lose, Outcome::Draw, Outcome::Win][second];
+                    game.response(Move(opponent), outcome)
+                }
+            };
+            *score = u64::from(game.play_game(Move(opponent), player).1);
+        }
+    }
+
+    let guide = {
+        let _parse = tracing::info_span!("parse").entered();
+        input::map(file_path)?
     };
-    let p2 = match input2 {
-        "X" => RPS::Rock,
-        "Y" => RPS::Paper,
-        "Z" => RPS::Scissors,
-        _ => panic!("Unexpected puzzle input: {input2}"),
+    let _solve = tracing::info_span!("solve").entered();
+    let guide = guide.strip_suffix(b"\n").unwrap_or(&guide);
+    if guide.is_empty() {
+        return Ok(0);
+    }
+    let mut total = Accumulator::new("score total");
+    for (idx, line) in guide.split(|byte| *byte == b'\n').enumerate() {
+        let line = line.strip_suffix(b"\r").unwrap_or(line);
+        let (opponent, second) = parse::guide_round(line).map_err(|e| e.at_line(idx + 1))?;
+        let second = match interpretation {
+            Interpretation::Moves => Move::try_from(second)?.0,
+            Interpretation::Outcomes => Outcome::try_from(second)? as usize,
+        };
+        let opponent = Move::try_from(opponent)?;
+        total.add(scores[opponent.0][second], Some(idx + 1))?;
+    }
+    Ok(total.total())
+}
+
+#[tracing::instrument]
+pub fn part1(file_path: &str) -> Result<u64, Box<dyn error::Error>> {
+    guide_total(file_path, Interpretation::Moves)
+}
+
+#[tracing::instrument]
+pub fn part2(file_path: &str) -> Result<u64, Box<dyn error::Error>> {
+    guide_total(file_path, Interpretation::Outcomes)
+}
+
+// The line-by-line 'token_pair' route that configured games take, on the puzzle's game, for the benchmark.
+pub fn part1_lines(file_path: &str) -> Result<u64, Box<dyn error::Error>> {
+    total_score(
+        file_path,
+        &Game::rock_paper_scissors(),
+        Interpretation::Moves,
+    )
+}
+
+// 'rounds' million random rounds, for 'bench --day 2'.
+pub fn generate(output: &mut dyn Write, rounds: u64) -> io::Result<()> {
+    let mut rng = Rng::new(2);
+    // Rounds are written a block at a time rather than one 'write' call each.
+    let mut block = Vec::with_capacity(4 * 4096);
+    for round in 0..rounds * 1_000_000 {
+        block.extend_from_slice(&[
+            b'A' + rng.below(3) as u8,
+            b' ',
+            b'X' + rng.below(3) as u8,
+            b'\n',
+        ]);
+        if round % 4096 == 4095 {
+            output.write_all(&block)?;
+            block.clear();
+        }
+    }
+    output.write_all(&block)
+}
+
+// One way of reading the strategy guide's second column, and the total score it gives.
+pub(crate) struct Reading {
+    pub(crate) description: String,
+    pub(crate) score: u64,
+}
+
+// All orderings of 0..n, in lexicographic order.
+fn permutations(n: usize) -> Vec<Vec<usize>> {
+    if n == 0 {
+        return vec![Vec::new()];
+    }
+    let mut all = Vec::new();
+    for first in 0..n {
+        for rest in permutations(n - 1) {
+            let mut permutation = vec![first];
+            permutation.extend(rest.into_iter().map(|i| if i >= first { i + 1 } else { i }));
+            all.push(permutation);
+        }
+    }
+    all
+}
+
+// Scores the guide under every assignment of the player symbols to moves (six for rock-paper-scissors)
+// and under the outcome interpretation. Rounds are counted by their symbols first, so each reading
+// only scores the distinct rounds rather than the whole guide.
+pub(crate) fn readings(
+    guide: impl BufRead,
+    game: &Game,
+) -> Result<Vec<Reading>, Box<dyn error::Error>> {
+    game.check_tables()?;
+    let mut counts: BTreeMap<(String, String), u64> = BTreeMap::new();
+    for (idx, result) in guide.lines().enumerate() {
+        let line = result?;
+        let (input1, input2) = parse::token_pair(&line).map_err(|e| e.at_line(idx + 1))?;
+        *counts
+            .entry((input1.to_string(), input2.to_string()))
+            .or_default() += 1;
+    }
+    let score = |game: &Game, interpretation| -> Result<u64, Box<dyn error::Error>> {
+        let mut total = Accumulator::new("score total");
+        for ((input1, input2), count) in &counts {
+            let (p1, p2) = game.decode(input1, input2, interpretation)?;
+            total.add(u64::from(game.play_game(p1, p2).1) * count, None)?;
+        }
+        Ok(total.total())
     };
-    (p1, p2)
+
+    let mut readings = Vec::new();
+    for permutation in permutations(game.names.len()) {
+        // The symbol at position 'i' of the player table now stands for move 'permutation[i]'.
+        let mut symbols = game.player_symbols.clone();
+        for (i, shape) in permutation.iter().enumerate() {
+            symbols[*shape] = game.player_symbols[i];
+        }
+        let description = game
+            .player_symbols
+            .iter()
+            .zip(&permutation)
+            .map(|(symbol, shape)| format!("{symbol}={}", game.names[*shape]))
+            .collect::<Vec<_>>()
+            .join(" ");
+        let permuted = game.clone().with_player_symbols(&symbols)?;
+        readings.push(Reading {
+            description,
+            score: score(&permuted, Interpretation::Moves)?,
+        });
+    }
+    let [lose, draw, win] = game.outcome_symbols;
+    readings.push(Reading {
+        description: format!("{lose}=lose {draw}=draw {win}=win"),
+        score: score(game, Interpretation::Outcomes)?,
+    });
+    Ok(readings)
+}
+
+// Every reading of the guide with its score, for 'run --day 2 --decode'. With a 'target' score,
+// the readings that give it are marked.
+pub fn decode(file_path: &str, target: Option<u64>) -> Result<String, Box<dyn error::Error>> {
+    let guide = input::reader(file_path)?;
+    Ok(decode_table(
+        &readings(guide, &Game::rock_paper_scissors())?,
+        target,
+    ))
+}
+
+fn decode_table(readings: &[Reading], target: Option<u64>) -> String {
+    let mut table = format!("{:<36} {:>10}", "Reading", "Score");
+    for reading in readings {
+        let mark = if Some(reading.score) == target {
+            "  <- target"
+        } else {
+            ""
+        };
+        table.push_str(&format!(
+            "\n{:<36} {:>10}{mark}",
+            reading.description, reading.score
+        ));
+    }
+    if let Some(target) = target {
+        let matches = readings
+            .iter()
+            .filter(|reading| reading.score == target)
+            .count();
+        table.push_str(&format!(
+            "\n{matches} reading(s) consistent with a score of {target}."
+        ));
+    }
+    table
+}
+
+// A response to every round of the opponent's column, and the total it scores.
+pub(crate) struct CounterPlan {
+    // The opponent's move and the player's response, per round.
+    pub(crate) rounds: Vec<(Move, Move)>,
+    pub(crate) score: u64,
+}
+
+// The first column of the strategy guide, ignoring the second.
+fn opponent_moves(file_path: &str, game: &Game) -> Result<Vec<Move>, Box<dyn error::Error>> {
+    let mut moves = Vec::new();
+    for (idx, result) in input::lines(file_path)?.enumerate() {
+        let line = result?;
+        let (input1, _) = parse::token_pair(&line).map_err(|e| e.at_line(idx + 1))?;
+        moves.push(Move(game.lookup(
+            "opponent",
+            &game.opponent_symbols,
+            input1,
+        )?));
+    }
+    Ok(moves)
 }
 
-fn get_player_choices2(input1: &str, input2: &str) -> (RPS, RPS) {
-    let p1 = match input1 {
-        "A" => RPS::Rock,
-        "B" => RPS::Paper,
-        "C" => RPS::Scissors,
-        _ => panic!("Unexpected puzzle input: {input1}"),
+// The highest-scoring responses to 'opponents', or with 'losses' the highest-scoring responses that
+// lose exactly that many rounds and win or draw the rest. Rounds are independent, so the constrained
+// plan plays the best non-losing move everywhere and then gives up the rounds where losing costs least.
+pub(crate) fn counter_strategy(
+    game: &Game,
+    opponents: &[Move],
+    losses: Option<usize>,
+) -> Result<CounterPlan, Box<dyn error::Error>> {
+    let best = |opponent, allowed: fn(Outcome) -> bool| {
+        game.best_response(opponent, allowed)
+            .expect("every move can be drawn against, won against and lost to")
     };
-    let p2 = match input2 {
-        "X" => p1.weaker(),
-        "Y" => p1.equal(),
-        "Z" => p1.stronger(),
-        _ => panic!("Unexpected puzzle input: {input2}"),
+    let mut responses: Vec<(Move, u32)> = match losses {
+        None => opponents
+            .iter()
+            .map(|opponent| best(*opponent, |_| true))
+            .collect(),
+        Some(losses) => {
+            if losses > opponents.len() {
+                return Err(GameError::Losses {
+                    losses,
+                    rounds: opponents.len(),
+                }
+                .into());
+            }
+            opponents
+                .iter()
+                .map(|opponent| best(*opponent, |outcome| outcome != Outcome::Lose))
+                .collect()
+        }
     };
-    (p1, p2)
+    if let Some(losses) = losses {
+        // Signed, as a game config could score a loss above a win.
+        let mut rounds: Vec<(i64, usize, (Move, u32))> = opponents
+            .iter()
+            .enumerate()
+            .map(|(idx, opponent)| {
+                let lose = best(*opponent, |outcome| outcome == Outcome::Lose);
+                (i64::from(responses[idx].1) - i64::from(lose.1), idx, lose)
+            })
+            .collect();
+        rounds.sort_by_key(|(cost, idx, _)| (*cost, *idx));
+        for (_, idx, lose) in rounds.into_iter().take(losses) {
+            responses[idx] = lose;
+        }
+    }
+
+    let mut total = Accumulator::new("score total");
+    for (idx, (_, score)) in responses.iter().enumerate() {
+        total.add(u64::from(*score), Some(idx + 1))?;
+    }
+    Ok(CounterPlan {
+        rounds: opponents
+            .iter()
+            .zip(&responses)
+            .map(|(opponent, (response, _))| (*opponent, *response))
+            .collect(),
+        score: total.total(),
+    })
 }
 
-fn play_game(p1: RPS, p2: RPS) -> (u32, u32) {
-    if p1.weaker() == p2 {
-        (p1.value() + WIN, p2.value() + LOSE)
-    } else if p1.equal() == p2 {
-        (p1.value() + DRAW, p2.value() + DRAW)
-    } else {
-        (p1.value() + LOSE, p2.value() + WIN)
+// The best counter-strategy to the opponent's column, for 'run --day 2 --counter': the maximum score,
+// the maximum when losing exactly 'losses' rounds, and the best fixed reading of X/Y/Z, followed by
+// the round-by-round choices of the constrained plan (or the unconstrained one without 'losses').
+pub fn counter(file_path: &str, losses: Option<usize>) -> Result<String, Box<dyn error::Error>> {
+    let game = Game::rock_paper_scissors();
+    let opponents = opponent_moves(file_path, &game)?;
+    let maximum = counter_strategy(&game, &opponents, None)?;
+    let mut report = format!("Maximum score: {}", maximum.score);
+    let plan = match losses {
+        Some(losses) => {
+            let constrained = counter_strategy(&game, &opponents, Some(losses))?;
+            report.push_str(&format!(
+                "\nMaximum score losing exactly {losses} round(s): {}",
+                constrained.score
+            ));
+            constrained
+        }
+        None => maximum,
+    };
+    // The last reading is the outcome interpretation; the rest are the fixed move mappings.
+    let mut mappings = readings(input::reader(file_path)?, &game)?;
+    mappings.pop();
+    if let Some(best) = mappings.iter().max_by_key(|reading| reading.score) {
+        report.push_str(&format!(
+            "\nBest fixed mapping: {} ({})",
+            best.description, best.score
+        ));
+    }
+
+    report.push_str(&format!(
+        "\n\n{:>6} {:<10} {:<10} {:<6} {:>6}",
+        "Round", "Opponent", "Response", "Result", "Score"
+    ));
+    for (idx, (opponent, response)) in plan.rounds.iter().enumerate() {
+        report.push_str(&format!(
+            "\n{:>6} {:<10} {:<10} {:<6} {:>6}",
+            idx + 1,
+            game.name(*opponent),
+            game.name(*response),
+            format!("{:?}", game.outcome(*response, *opponent)),
+            game.play_game(*opponent, *response).1
+        ));
+    }
+    Ok(report)
+}
+
+const ELO_START: f64 = 1500.0;
+const ELO_K: f64 = 32.0;
+
+// A player's line in the league table. Wins, draws and losses count single rounds against single
+// opponents, and points are the sum of 'play_game' scores over them.
+pub(crate) struct Standing {
+    pub(crate) name: String,
+    pub(crate) wins: u64,
+    pub(crate) draws: u64,
+    pub(crate) losses: u64,
+    pub(crate) points: u64,
+}
+
+pub(crate) struct Tournament {
+    // In player order; see 'league_table' for the ranking.
+    pub(crate) standings: Vec<Standing>,
+    pub(crate) rounds: usize,
+    // Every player's rating before the first round and after each round, when Elo is tracked.
+    pub(crate) ratings: Option<Vec<Vec<f64>>>,
+}
+
+impl Tournament {
+    // Standings by points, then wins, then name.
+    pub(crate) fn league_table(&self) -> Vec<&Standing> {
+        let mut table: Vec<&Standing> = self.standings.iter().collect();
+        table.sort_by(|a, b| {
+            (b.points, b.wins)
+                .cmp(&(a.points, a.wins))
+                .then_with(|| a.name.cmp(&b.name))
+        });
+        table
+    }
+}
+
+// The player's own moves from a strategy guide: the second column, read with 'interpretation'.
+fn player_moves(
+    file_path: &str,
+    game: &Game,
+    interpretation: Interpretation,
+) -> Result<Vec<Move>, Box<dyn error::Error>> {
+    let mut moves = Vec::new();
+    for (idx, result) in input::lines(file_path)?.enumerate() {
+        let line = result?;
+        let (input1, input2) = parse::token_pair(&line).map_err(|e| e.at_line(idx + 1))?;
+        moves.push(game.decode(input1, input2, interpretation)?.1);
+    }
+    Ok(moves)
+}
+
+// Every player meets every other player in every round, playing their guide's move for that round.
+// The tournament lasts as long as the shortest guide. With 'elo', ratings are updated after each round
+// from the ratings going into it, so the order of the pairings within a round does not matter.
+pub(crate) fn tournament(
+    game: &Game,
+    players: &[(String, Vec<Move>)],
+    elo: bool,
+) -> Result<Tournament, Box<dyn error::Error>> {
+    if players.len() < 2 {
+        return Err(GameError::Players(players.len()).into());
+    }
+    let rounds = players
+        .iter()
+        .map(|(_, moves)| moves.len())
+        .min()
+        .unwrap_or_default();
+    let mut standings: Vec<Standing> = players
+        .iter()
+        .map(|(name, _)| Standing {
+            name: name.clone(),
+            wins: 0,
+            draws: 0,
+            losses: 0,
+            points: 0,
+        })
+        .collect();
+    // Points go through accumulators like every other score, so '--checked' catches an overflow;
+    // they are copied into the standings once the tournament is over.
+    let mut points: Vec<Accumulator> = players
+        .iter()
+        .map(|_| Accumulator::new("tournament points"))
+        .collect();
+    let mut ratings = elo.then(|| vec![vec![ELO_START; players.len()]]);
+
+    for round in 0..rounds {
+        let mut changes = vec![0.0; players.len()];
+        for i in 0..players.len() {
+            for j in i + 1..players.len() {
+                let (a, b) = (players[i].1[round], players[j].1[round]);
+                let (score_a, score_b) = game.play_game(a, b);
+                points[i].add(u64::from(score_a), Some(round + 1))?;
+                points[j].add(u64::from(score_b), Some(round + 1))?;
+                // 'a''s result, as Elo scores it.
+                let result = match game.outcome(a, b) {
+                    Outcome::Win => {
+                        standings[i].wins += 1;
+                        standings[j].losses += 1;
+                        1.0
+                    }
+                    Outcome::Draw => {
+                        standings[i].draws += 1;
+                        standings[j].draws += 1;
+                        0.5
+                    }
+                    Outcome::Lose => {
+                        standings[i].losses += 1;
+                        standings[j].wins += 1;
+                        0.0
+                    }
+                };
+                if let Some(history) = &ratings {
+                    let current = history.last().expect("starts with the initial ratings");
+                    let expected = 1.0 / (1.0 + 10f64.powf((current[j] - current[i]) / 400.0));
+                    changes[i] += ELO_K * (result - expected);
+                    changes[j] -= ELO_K * (result - expected);
+                }
+            }
+        }
+        if let Some(history) = &mut ratings {
+            let current = history.last().expect("starts with the initial ratings");
+            let next = current.iter().zip(&changes).map(|(r, c)| r + c).collect();
+            history.push(next);
+        }
+    }
+    for (standing, points) in standings.iter_mut().zip(&points) {
+        standing.points = points.total();
+    }
+    Ok(Tournament {
+        standings,
+        rounds,
+        ratings,
+    })
+}
+
+// A round-robin between the strategy guides in 'file_paths', for 'run --day 2 --tournament FILE...'.
+// Players are named after their files. With 'elo', ratings are shown at (up to) ten points in the tournament.
+pub fn play_tournament(file_paths: &[PathBuf], elo: bool) -> Result<String, Box<dyn error::Error>> {
+    let game = Game::rock_paper_scissors();
+    let mut players = Vec::new();
+    for path in file_paths {
+        let name = path
+            .file_stem()
+            .unwrap_or(path.as_os_str())
+            .to_string_lossy()
+            .into_owned();
+        let moves = player_moves(&path.to_string_lossy(), &game, Interpretation::Moves)?;
+        players.push((name, moves));
+    }
+    let tournament = tournament(&game, &players, elo)?;
+
+    let mut report = format!(
+        "{} round(s), {} player(s)\n\n{:<4} {:<20} {:>8} {:>8} {:>8} {:>10}",
+        tournament.rounds,
+        players.len(),
+        "Rank",
+        "Player",
+        "Won",
+        "Drawn",
+        "Lost",
+        "Points"
+    );
+    for (rank, standing) in tournament.league_table().iter().enumerate() {
+        report.push_str(&format!(
+            "\n{:<4} {:<20} {:>8} {:>8} {:>8} {:>10}",
+            rank + 1,
+            standing.name,
+            standing.wins,
+            standing.draws,
+            standing.losses,
+            standing.points
+        ));
+    }
+
+    if let Some(history) = &tournament.ratings {
+        report.push_str(&format!("\n\n{:<8}", "Round"));
+        for (name, _) in &players {
+            report.push_str(&format!(" {name:>12}"));
+        }
+        let step = tournament.rounds.div_ceil(10).max(1);
+        let mut checkpoints: Vec<usize> = (0..=tournament.rounds).step_by(step).collect();
+        if checkpoints.last() != Some(&tournament.rounds) {
+            checkpoints.push(tournament.rounds);
+        }
+        for round in checkpoints {
+            report.push_str(&format!("\n{round:<8}"));
+            for rating in &history[round] {
+                report.push_str(&format!(" {rating:>12.1}"));
+            }
+        }
+    }
+    Ok(report)
+}
+
+// What 'export_ledger' writes; main.rs maps its '--ledger' values onto this.
+#[derive(Clone, Copy, Debug)]
+pub(crate) enum LedgerFormat {
+    Csv,
+    Json,
+}
+
+// One round of the guide under one interpretation, as the ledger shows it. The opponent is player 1.
+#[derive(serde::Serialize)]
+pub(crate) struct LedgerRow {
+    pub(crate) interpretation: Interpretation,
+    pub(crate) round: usize,
+    pub(crate) opponent_symbol: String,
+    pub(crate) player_symbol: String,
+    pub(crate) opponent_move: String,
+    pub(crate) player_move: String,
+    // From the player's side.
+    pub(crate) outcome: Outcome,
+    pub(crate) opponent_shape_points: u32,
+    pub(crate) opponent_outcome_points: u32,
+    pub(crate) player_shape_points: u32,
+    pub(crate) player_outcome_points: u32,
+    pub(crate) opponent_total: u64,
+    pub(crate) player_total: u64,
+}
+
+// Every round of the guide under 'interpretation', with both players' running totals. The points
+// are split by 'Game::points', the same scoring 'play_game' adds up, so each row's points sum to
+// what 'play_game' gives for the round.
+pub(crate) fn ledger(
+    guide: impl BufRead,
+    game: &Game,
+    interpretation: Interpretation,
+) -> Result<Vec<LedgerRow>, Box<dyn error::Error>> {
+    let mut opponent_total = Accumulator::new("opponent score total");
+    let mut player_total = Accumulator::new("score total");
+    let mut rows = Vec::new();
+    for (idx, result) in guide.lines().enumerate() {
+        let line = result?;
+        let (input1, input2) = parse::token_pair(&line).map_err(|e| e.at_line(idx + 1))?;
+        let (p1, p2) = game.decode(input1, input2, interpretation)?;
+        let (opponent_shape_points, opponent_outcome_points) = game.points(p1, p2);
+        let (player_shape_points, player_outcome_points) = game.points(p2, p1);
+        opponent_total.add(
+            u64::from(opponent_shape_points + opponent_outcome_points),
+            Some(idx + 1),
+        )?;
+        player_total.add(
+            u64::from(player_shape_points + player_outcome_points),
+            Some(idx + 1),
+        )?;
+        rows.push(LedgerRow {
+            interpretation,
+            round: idx + 1,
+            opponent_symbol: String::from(input1),
+            player_symbol: String::from(input2),
+            opponent_move: String::from(game.name(p1)),
+            player_move: String::from(game.name(p2)),
+            outcome: game.outcome(p2, p1),
+            opponent_shape_points,
+            opponent_outcome_points,
+            player_shape_points,
+            player_outcome_points,
+            opponent_total: opponent_total.total(),
+            player_total: player_total.total(),
+        });
+    }
+    Ok(rows)
+}
+
+// The ledgers of both interpretations (part 1's, then part 2's) as CSV or a JSON array,
+// for 'run --day 2 --ledger FORMAT'.
+pub fn export_ledger(
+    file_path: &str,
+    format: LedgerFormat,
+) -> Result<String, Box<dyn error::Error>> {
+    let game = Game::rock_paper_scissors();
+    let mut rows = ledger(input::reader(file_path)?, &game, Interpretation::Moves)?;
+    rows.extend(ledger(
+        input::reader(file_path)?,
+        &game,
+        Interpretation::Outcomes,
+    )?);
+    render_ledger(&rows, format)
+}
+
+fn render_ledger(
+    rows: &[LedgerRow],
+    format: LedgerFormat,
+) -> Result<String, Box<dyn error::Error>> {
+    match format {
+        LedgerFormat::Json => Ok(serde_json::to_string_pretty(&rows)?),
+        LedgerFormat::Csv => {
+            // None of the fields can contain a comma or a quote, so nothing needs escaping.
+            let mut csv = String::from(
+                "interpretation,round,opponent_symbol,player_symbol,opponent_move,player_move,outcome,\
+                 opponent_shape_points,opponent_outcome_points,player_shape_points,player_outcome_points,\
+                 opponent_total,player_total",
+            );
+            for row in rows {
+                csv.push_str(&format!(
+                    "\n{},{},{},{},{},{},{},{},{},{},{},{},{}",
+                    format!("{:?}", row.interpretation).to_lowercase(),
+                    row.round,
+                    row.opponent_symbol,
+                    row.player_symbol,
+                    row.opponent_move,
+                    row.player_move,
+                    format!("{:?}", row.outcome).to_lowercase(),
+                    row.opponent_shape_points,
+                    row.opponent_outcome_points,
+                    row.player_shape_points,
+                    row.player_outcome_points,
+                    row.opponent_total,
+                    row.player_total
+                ));
+            }
+            Ok(csv)
+        }
+    }
+}
+
+// Both parts' totals under a game loaded from 'config_path', for 'run --day 2 --game FILE'.
+pub fn play_config(file_path: &str, config_path: &Path) -> Result<String, Box<dyn error::Error>> {
+    let game = Game::load(config_path)?;
+    let moves = total_score(file_path, &game, Interpretation::Moves)?;
+    let outcomes = total_score(file_path, &game, Interpretation::Outcomes)?;
+    Ok(format!(
+        "Part 1 (moves): {moves}\nPart 2 (outcomes): {outcomes}"
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: [(&str, &str); 3] = [("A", "Y"), ("B", "X"), ("C", "Z")];
+
+    fn example_score(game: &Game, interpretation: Interpretation) -> u32 {
+        EXAMPLE
+            .iter()
+            .map(|(opponent, second)| {
+                let (p1, p2) = game.decode(opponent, second, interpretation).unwrap();
+                game.play_game(p1, p2).1
+            })
+            .sum()
+    }
+
+    #[test]
+    fn test_rock_paper_scissors() {
+        let game = Game::rock_paper_scissors();
+        assert_eq!(example_score(&game, Interpretation::Moves), 15);
+        assert_eq!(example_score(&game, Interpretation::Outcomes), 12);
+        assert!(game.beats(Move(1), Move(0)));
+        assert!(game.beats(Move(0), Move(2)));
+        assert!(game.decode("A", "W", Interpretation::Moves).is_err());
+        assert_eq!(Move::try_from('B').unwrap(), Move::try_from('Y').unwrap());
+        assert_eq!(Outcome::try_from('Z').unwrap(), Outcome::Win);
+        assert!(Outcome::try_from('C').is_err());
+    }
+
+    #[test]
+    fn test_counter_strategy() {
+        let game = Game::rock_paper_scissors();
+        let opponents = [Move(0), Move(1), Move(2)];
+        let maximum = counter_strategy(&game, &opponents, None).unwrap();
+        assert_eq!(maximum.score, 8 + 9 + 7);
+        assert_eq!(maximum.rounds[0], (Move(0), Move(1)));
+        // Losing costs 5 points against Rock or Scissors and 8 against Paper; ties go to the earlier round.
+        let one_loss = counter_strategy(&game, &opponents, Some(1)).unwrap();
+        assert_eq!(one_loss.score, 24 - 5);
+        assert_eq!(one_loss.rounds[0], (Move(0), Move(2)));
+        assert_eq!(
+            counter_strategy(&game, &opponents, Some(3)).unwrap().score,
+            3 + 1 + 2
+        );
+        assert!(counter_strategy(&game, &opponents, Some(4)).is_err());
+    }
+
+    #[test]
+    fn test_tournament() {
+        let game = Game::rock_paper_scissors();
+        let player = |name: &str, shape| (String::from(name), vec![Move(shape); 2]);
+        let players = [player("rock", 0), player("paper", 1), player("scissors", 2)];
+        let result = tournament(&game, &players, true).unwrap();
+        assert_eq!(result.rounds, 2);
+        for standing in &result.standings {
+            assert_eq!((standing.wins, standing.draws, standing.losses), (2, 0, 2));
+        }
+        // Rock scores 7 against scissors and 1 against paper each round.
+        assert_eq!(result.standings[0].points, 16);
+        assert_eq!(result.league_table()[0].name, "scissors");
+        // A perfect cycle leaves every rating where it started.
+        let ratings = result.ratings.unwrap();
+        assert_eq!(ratings.len(), 3);
+        assert!(ratings[2]
+            .iter()
+            .all(|rating| (rating - ELO_START).abs() < 1e-9));
+
+        let result = tournament(&game, &players[..2], true).unwrap();
+        let ratings = result.ratings.unwrap();
+        assert_eq!(ratings[1], [ELO_START - 16.0, ELO_START + 16.0]);
+        assert!(ratings[2][1] > ratings[1][1]);
+        assert_eq!(
+            tournament(&game, &players[..1], false)
+                .map(|_| ())
+                .unwrap_err()
+                .to_string(),
+            "A tournament needs at least two players, not 1."
+        );
+    }
+
+    #[test]
+    fn test_readings() {
+        let guide = "A Y\nB X\nC Z\n";
+        let readings = readings(guide.as_bytes(), &Game::rock_paper_scissors()).unwrap();
+        // Six move mappings, then the outcome interpretation.
+        assert_eq!(readings.len(), 7);
+        assert_eq!(readings[0].description, "X=Rock Y=Paper Z=Scissors");
+        assert_eq!(readings[0].score, 15);
+        assert_eq!(readings[6].description, "X=lose Y=draw Z=win");
+        assert_eq!(readings[6].score, 12);
+
+        let table = decode_table(&readings, Some(15));
+        let marked: Vec<&str> = table
+            .lines()
+            .filter_map(|row| row.strip_suffix("<- target"))
+            .map(|row| row.split("  ").next().unwrap())
+            .collect();
+        assert_eq!(
+            marked,
+            [
+                "X=Rock Y=Paper Z=Scissors",
+                "X=Paper Y=Rock Z=Scissors",
+                "X=Paper Y=Scissors Z=Rock",
+                "X=Scissors Y=Rock Z=Paper",
+            ]
+        );
+        assert!(table.ends_with("4 reading(s) consistent with a score of 15."));
+        assert!(!decode_table(&readings, None).contains("<- target"));
+    }
+
+    #[test]
+    fn test_ledger() {
+        let guide = "A Y\nB X\nC Z\n";
+        let game = Game::rock_paper_scissors();
+        // Per round: (opponent shape, opponent outcome, player shape, player outcome) points,
+        // then the running (opponent, player) totals.
+        let expected = [
+            (
+                Interpretation::Moves,
+                [(1, 0, 2, 6), (2, 6, 1, 0), (3, 3, 3, 3)],
+                [(1, 8), (9, 9), (15, 15)],
+            ),
+            (
+                Interpretation::Outcomes,
+                [(1, 3, 1, 3), (2, 6, 1, 0), (3, 0, 1, 6)],
+                [(4, 4), (12, 5), (15, 12)],
+            ),
+        ];
+        let mut all_rows = Vec::new();
+        for (interpretation, points, totals) in expected {
+            let rows = ledger(guide.as_bytes(), &game, interpretation).unwrap();
+            let found: Vec<_> = rows
+                .iter()
+                .map(|row| {
+                    (
+                        row.opponent_shape_points,
+                        row.opponent_outcome_points,
+                        row.player_shape_points,
+                        row.player_outcome_points,
+                    )
+                })
+                .collect();
+            assert_eq!(found, points);
+            let found: Vec<_> = rows
+                .iter()
+                .map(|row| (row.opponent_total, row.player_total))
+                .collect();
+            assert_eq!(found, totals);
+            all_rows.extend(rows);
+        }
+
+        let csv = render_ledger(&all_rows, LedgerFormat::Csv).unwrap();
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(lines.len(), 7);
+        assert!(lines[0].starts_with("interpretation,round,opponent_symbol,"));
+        assert!(lines.iter().all(|line| line.split(',').count() == 13));
+        assert_eq!(lines[1], "moves,1,A,Y,Rock,Paper,win,1,0,2,6,1,8");
+        assert_eq!(lines[6], "outcomes,3,C,Z,Scissors,Rock,win,3,0,1,6,15,12");
+    }
+
+    #[test]
+    fn test_permutations() {
+        let all = permutations(3);
+        assert_eq!(all.len(), 6);
+        assert_eq!(all[0], [0, 1, 2]);
+        assert_eq!(all[5], [2, 1, 0]);
+        assert_eq!(permutations(5).len(), 120);
+    }
+
+    #[test]
+    fn test_rock_paper_scissors_lizard_spock() {
+        let game = Game::new(&["Rock", "Spock", "Paper", "Lizard", "Scissors"])
+            .unwrap()
+            .with_player_symbols(&['V', 'W', 'X', 'Y', 'Z'])
+            .unwrap();
+        let find = |name| {
+            Move(
+                game.names
+                    .iter()
+                    .position(|candidate| *candidate == name)
+                    .unwrap(),
+            )
+        };
+        let wins = [
+            ("Scissors", "Paper"),
+            ("Paper", "Rock"),
+            ("Rock", "Lizard"),
+            ("Lizard", "Spock"),
+            ("Spock", "Scissors"),
+            ("Scissors", "Lizard"),
+            ("Lizard", "Paper"),
+            ("Paper", "Spock"),
+            ("Spock", "Rock"),
+            ("Rock", "Scissors"),
+        ];
+        for (winner, loser) in wins {
+            assert!(
+                game.beats(find(winner), find(loser)),
+                "{winner} beats {loser}"
+            );
+            assert!(!game.beats(find(loser), find(winner)));
+        }
+        for opponent in 0..5 {
+            for outcome in [Outcome::Lose, Outcome::Draw, Outcome::Win] {
+                let response = game.response(Move(opponent), outcome);
+                assert_eq!(game.outcome(response, Move(opponent)), outcome);
+            }
+        }
+        assert!(matches!(
+            Game::new(&["Rock", "Paper"]),
+            Err(GameError::MoveCount(2))
+        ));
+
+        // Five moves and no player symbols would leave moves without a symbol.
+        let unsymbolled = Game::new(&["Rock", "Spock", "Paper", "Lizard", "Scissors"]).unwrap();
+        assert!(matches!(
+            unsymbolled.check_tables(),
+            Err(GameError::TableSize {
+                table: "player symbol",
+                expected: 5,
+                found: 0
+            })
+        ));
+        assert!(matches!(
+            game.clone()
+                .with_opponent_symbols(&['A', 'B', 'C', 'B', 'E']),
+            Err(GameError::DuplicateSymbol {
+                table: "opponent symbol",
+                symbol: 'B'
+            })
+        ));
+        assert!(matches!(
+            game.with_outcome_symbols('L', 'D', 'L'),
+            Err(GameError::DuplicateSymbol {
+                table: "outcome symbol",
+                symbol: 'L'
+            })
+        ));
     }
 }
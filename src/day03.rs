@@ -1,10 +1,14 @@
 use std::collections::HashMap;
-use std::fs;
+use std::error;
+use std::io;
+
+use crate::arith::Accumulator;
+use crate::input;
 
 // 'for' loops (like functions) cannot be used with 'const' or 'static'
 //  - 'const' values are inlined to each place they're used at compile time.
-//  - 'static' values are not inlined like 'const' but reside at a fixed location in memory 
-//    (evaluated at compile time). 
+//  - 'static' values are not inlined like 'const' but reside at a fixed location in memory
+//    (evaluated at compile time).
 //
 // 'lazy_static' allows 'static'-like behaviour on variables that need to be evaluated at runtime
 //    (which is done lazily; when the variable is first accessed).
@@ -35,11 +39,25 @@ struct Item {
     priority: u32,
 }
 
-#[derive(Debug)]
+#[derive(Debug, thiserror::Error)]
 enum ItemError {
+    #[error("Invalid item: {0}.")]
     InvalidChar(char),
 }
 
+// What went wrong, and on which line of the input.
+#[derive(Debug, thiserror::Error)]
+enum RuckSackError {
+    #[error("Line {line}: {source}")]
+    Item { line: usize, source: ItemError },
+    #[error("Line {0}: no item is in both compartments.")]
+    NoDuplicate(usize),
+    #[error("The group starting at line {0} has fewer than three rucksacks.")]
+    IncompleteGroup(usize),
+    #[error("Lines {0} to {1}: no item is in all three rucksacks.")]
+    NoBadge(usize, usize),
+}
+
 // OOP-style solutions are almost always the incorrect approach.
 // - "Object orientated programs are offered as alternatives to correct ones..." ~ Edsger Dijkstra
 // - "Object-Oriented Programming is Bad" ~ Brian Will
@@ -48,10 +66,10 @@ enum ItemError {
 // (It is still a good idea to encapsulate the data in a data structure.)
 impl Item {
     fn new(letter: char) -> Result<Self, ItemError> {
-        if !letter.is_alphabetic() {
-            return Err(ItemError::InvalidChar(letter));
-        }
-        let priority = *PRIORITY_MAP.get(&letter).unwrap();
+        // Only 'a'-'z' and 'A'-'Z' have priorities; other letters (like 'é') are invalid too.
+        let priority = *PRIORITY_MAP
+            .get(&letter)
+            .ok_or(ItemError::InvalidChar(letter))?;
         Ok(Self { letter, priority })
     }
 }
@@ -85,14 +103,8 @@ struct RuckSack {
 impl RuckSack {
     fn try_from(input: &str) -> Result<Self, ItemError> {
         let (left, right) = input.split_at(input.len() / 2);
-        let compartment1 = match Compartment::try_from(left) {
-            Ok(compartment) => compartment,
-            Err(e) => return Err(e),
-        };
-        let compartment2 = match Compartment::try_from(right) {
-            Ok(compartment) => compartment,
-            Err(e) => return Err(e),
-        };
+        let compartment1 = Compartment::try_from(left)?;
+        let compartment2 = Compartment::try_from(right)?;
         Ok(Self {
             compartment1,
             compartment2,
@@ -120,7 +132,7 @@ impl RuckSack {
 //    arise from calling state-edit methods in multiple places (a pitfull that doesn't befall pure functions).
 //  - Essentially, if a function is only called from a single place, consider inlining it.
 //
-// Additionaly, 
+// Additionaly,
 //  - Use large comment blocks inside the major function to delimit the (inligned) "minor functions".
 //  - Use Rust's {} to enforce scoping rules.
 //
@@ -143,28 +155,103 @@ fn find_group_badge_priority(
     None
 }
 
-pub fn part1(file_path: &str) -> u32 {
-    let puzzle_input = fs::read_to_string(file_path).unwrap();
-    let lines = puzzle_input.split('\n');
-    let mut total = 0;
-    for line in lines {
-        let rucksack = RuckSack::try_from(line).unwrap();
-        let priority = rucksack.find_duplicate_priority().unwrap();
-        total += priority;
+// Every rucksack is parsed before any is solved, so each part has one 'parse' and one 'solve' span.
+#[tracing::instrument]
+pub fn part1(file_path: &str) -> Result<u64, Box<dyn error::Error>> {
+    let rucksacks = {
+        let _parse = tracing::info_span!("parse").entered();
+        let mut rucksacks = Vec::new();
+        for (idx, result) in input::lines(file_path)?.enumerate() {
+            let line = idx + 1;
+            let rucksack = RuckSack::try_from(&result?)
+                .map_err(|source| RuckSackError::Item { line, source })?;
+            rucksacks.push(rucksack);
+        }
+        rucksacks
+    };
+    let _solve = tracing::info_span!("solve").entered();
+    let mut total = Accumulator::new("priority total");
+    for (idx, rucksack) in rucksacks.iter().enumerate() {
+        let priority = rucksack
+            .find_duplicate_priority()
+            .ok_or(RuckSackError::NoDuplicate(idx + 1))?;
+        tracing::trace!(priority, "duplicate item");
+        total.add(u64::from(priority), Some(idx + 1))?;
+    }
+    Ok(total.total())
+}
+
+// The next numbered line of a group that starts at 'group_start' (1-based), as a rucksack.
+fn group_member(
+    entry: Option<(usize, io::Result<String>)>,
+    group_start: usize,
+) -> Result<RuckSack, Box<dyn error::Error>> {
+    let (idx, line) = entry.ok_or(RuckSackError::IncompleteGroup(group_start))?;
+    let rucksack = RuckSack::try_from(&line?).map_err(|source| RuckSackError::Item {
+        line: idx + 1,
+        source,
+    })?;
+    Ok(rucksack)
+}
+
+#[tracing::instrument]
+pub fn part2(file_path: &str) -> Result<u64, Box<dyn error::Error>> {
+    // Each group with the line it starts at.
+    let groups = {
+        let _parse = tracing::info_span!("parse").entered();
+        let mut lines = input::lines(file_path)?.enumerate();
+        let mut groups = Vec::new();
+        while let Some((idx, first)) = lines.next() {
+            let group_start = idx + 1;
+            let group = (
+                group_member(Some((idx, first)), group_start)?,
+                group_member(lines.next(), group_start)?,
+                group_member(lines.next(), group_start)?,
+            );
+            groups.push((group_start, group));
+        }
+        groups
+    };
+    let _solve = tracing::info_span!("solve").entered();
+    let mut total = Accumulator::new("priority total");
+    for (group_start, (rucksack1, rucksack2, rucksack3)) in groups {
+        let group_end = group_start + 2;
+        let priority = find_group_badge_priority(rucksack1, rucksack2, rucksack3)
+            .ok_or(RuckSackError::NoBadge(group_start, group_end))?;
+        tracing::trace!(priority, "group badge");
+        total.add(u64::from(priority), Some(group_end))?;
     }
-    total
+    Ok(total.total())
 }
 
-pub fn part2(file_path: &str) -> u32 {
-    let puzzle_input = fs::read_to_string(file_path).unwrap();
-    let mut puzzle_input_iter = puzzle_input.split('\n').into_iter();
-    let mut total = 0;
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rucksack_errors() {
+        assert_eq!(Item::new('p').unwrap().priority, 16);
+        assert_eq!(Item::new('L').unwrap().priority, 38);
+        assert!(matches!(Item::new('é'), Err(ItemError::InvalidChar('é'))));
 
-    while let Some(line) = puzzle_input_iter.next() {
-        let rucksack1 = RuckSack::try_from(line).unwrap();
-        let rucksack2 = RuckSack::try_from(puzzle_input_iter.next().unwrap()).unwrap();
-        let rucksack3 = RuckSack::try_from(puzzle_input_iter.next().unwrap()).unwrap();
-        total += find_group_badge_priority(rucksack1, rucksack2, rucksack3).unwrap();
+        let rucksack = RuckSack::try_from("vJrwpWtwJgWrhcsFMMfFFhFp").unwrap();
+        assert_eq!(rucksack.find_duplicate_priority(), Some(16));
+        assert_eq!(
+            RuckSack::try_from("abcd")
+                .unwrap()
+                .find_duplicate_priority(),
+            None
+        );
+        assert_eq!(
+            group_member(None, 4).map(|_| ()).unwrap_err().to_string(),
+            "The group starting at line 4 has fewer than three rucksacks."
+        );
+        assert_eq!(
+            group_member(Some((5, Ok(String::from("ab1c")))), 4)
+                .map(|_| ())
+                .unwrap_err()
+                .to_string(),
+            "Line 6: Invalid item: 1."
+        );
     }
-    total
 }
@@ -1,4 +1,8 @@
-use std::fs;
+use std::error;
+
+use crate::arith::Accumulator;
+use crate::input;
+use crate::parse;
 
 mod elf {
     use std::ops::RangeInclusive;
@@ -9,24 +13,9 @@ mod elf {
     }
 
     impl ElfSections {
-        // 'Box' is a smart pointer that is used to allocate memory on the heap and store values in it.
-        // It provides ownership and ensures that the memory is properly deallocated when it goes out of scope.
-        //
-        // 'dyn' is a prefex of a trait object's type. 'dyn' because the compiler uses dynamic dispatch
-        // to determine the type of returned object at runtime.
-        // The compiler guarantees that the any returned object implements the trait 'std::error::Error'.
-        pub(crate) fn try_from(string: &str) -> Result<ElfSections, Box<dyn std::error::Error>> {
-            let mut string_split = string.split('-');
-            let start_section = string_split
-                .next()
-                .ok_or("Missing section")?
-                .parse::<u32>()?;
-            let end_section = string_split
-                .next()
-                .ok_or("Missing section")?
-                .parse::<u32>()?;
+        pub(crate) fn new((start_section, end_section): (u32, u32)) -> ElfSections {
             let section_range = RangeInclusive::new(start_section, end_section);
-            Ok(Self { section_range })
+            Self { section_range }
         }
 
         pub(crate) fn contains(&self, other: &ElfSections) -> bool {
@@ -41,32 +30,52 @@ mod elf {
     }
 }
 
-pub fn part1(file_path: &str) -> u32 {
-    let puzzle_input = fs::read_to_string(file_path).unwrap();
-    let lines = puzzle_input.split('\n');
-    let mut total = 0;
-    for line in lines {
-        let mut line_split = line.split(',');
-        let elf1 = elf::ElfSections::try_from(line_split.next().unwrap()).unwrap();
-        let elf2 = elf::ElfSections::try_from(line_split.next().unwrap()).unwrap();
-        if elf1.contains(&elf2) || elf2.contains(&elf1) || elf1 == elf2 {
-            total += 1;
+// 'Box' is a smart pointer that is used to allocate memory on the heap and store values in it.
+// It provides ownership and ensures that the memory is properly deallocated when it goes out of scope.
+//
+// 'dyn' is a prefex of a trait object's type. 'dyn' because the compiler uses dynamic dispatch
+// to determine the type of returned object at runtime.
+// The compiler guarantees that the any returned object implements the trait 'std::error::Error'.
+//
+// Every pair is parsed before any is solved, so each part has one 'parse' and one 'solve' span.
+fn parse_pairs(
+    file_path: &str,
+) -> Result<Vec<(elf::ElfSections, elf::ElfSections)>, Box<dyn error::Error>> {
+    let _parse = tracing::info_span!("parse").entered();
+    let mut pairs = Vec::new();
+    for (idx, result) in input::lines(file_path)?.enumerate() {
+        let line = result?;
+        let (sections1, sections2) = parse::range_pair(&line).map_err(|e| e.at_line(idx + 1))?;
+        pairs.push((
+            elf::ElfSections::new(sections1),
+            elf::ElfSections::new(sections2),
+        ));
+    }
+    Ok(pairs)
+}
+
+#[tracing::instrument]
+pub fn part1(file_path: &str) -> Result<u64, Box<dyn error::Error>> {
+    let pairs = parse_pairs(file_path)?;
+    let _solve = tracing::info_span!("solve").entered();
+    let mut total = Accumulator::new("containment count");
+    for (idx, (elf1, elf2)) in pairs.iter().enumerate() {
+        if elf1.contains(elf2) || elf2.contains(elf1) || elf1 == elf2 {
+            total.add(1, Some(idx + 1))?;
         }
     }
-    total
+    Ok(total.total())
 }
 
-pub fn part2(file_path: &str) -> u32 {
-    let puzzle_input = fs::read_to_string(file_path).unwrap();
-    let lines = puzzle_input.split('\n');
-    let mut total = 0;
-    for line in lines {
-        let mut line_split = line.split(',');
-        let elf1 = elf::ElfSections::try_from(line_split.next().unwrap()).unwrap();
-        let elf2 = elf::ElfSections::try_from(line_split.next().unwrap()).unwrap();
-        if elf1.overlaps(&elf2) {
-            total += 1;
+#[tracing::instrument]
+pub fn part2(file_path: &str) -> Result<u64, Box<dyn error::Error>> {
+    let pairs = parse_pairs(file_path)?;
+    let _solve = tracing::info_span!("solve").entered();
+    let mut total = Accumulator::new("overlap count");
+    for (idx, (elf1, elf2)) in pairs.iter().enumerate() {
+        if elf1.overlaps(elf2) {
+            total.add(1, Some(idx + 1))?;
         }
     }
-    total
+    Ok(total.total())
 }
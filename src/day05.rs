@@ -1,8 +1,19 @@
 use std::collections::HashMap;
 use std::collections::VecDeque;
+use std::error;
+use std::fmt;
 use std::fs;
-use std::num::ParseIntError;
 
+use crate::explore;
+use crate::grid::{Grid, Point};
+use crate::parse;
+use crate::search::{self, Options};
+use crate::viz::{self, Colour, Frame};
+
+// The stacks in key order, as a hashable search state.
+type Arrangement = Vec<VecDeque<String>>;
+
+#[derive(Clone)]
 struct Supplies {
     stacks_map: HashMap<String, VecDeque<String>>,
 }
@@ -15,40 +26,46 @@ enum SuppliesError {
     EmptyStack,
     #[error("Provided stack key does not exist: {0}.")]
     MissingKey(String),
+    #[error("The target arrangement has different stacks or crates.")]
+    Unreachable,
+    #[error("No rearrangement found within the search limit.")]
+    NoPlan,
 }
 
 impl Supplies {
+    // Each stack is one column of the drawing: the key sits on the bottom line and the crates are
+    // read from just above it upwards. Columns holding brackets or padding have no key and are skipped,
+    // and short (right-trimmed) lines are padded by the grid, so stacks may start out empty.
+    #[tracing::instrument(name = "parse", skip_all)]
     fn try_from(puzzle_input: &str) -> Result<Self, SuppliesError> {
-        let mut puzzle_lines: Vec<&str> = puzzle_input.split('\n').collect();
-        let stack_keys_line = puzzle_lines.remove(puzzle_lines.len() - 1);
-        let stack_keys: Vec<&str> = stack_keys_line.split_whitespace().collect();
+        let drawing = Grid::parse(puzzle_input);
         let mut stacks_map: HashMap<String, VecDeque<String>> = HashMap::new();
 
-        for line in puzzle_lines {
-            let mut chars = line.chars();
-            for stack_key in &stack_keys {
-                chars.next(); // [
-                let supply_crate = chars.next().ok_or(SuppliesError::ParseFailure)?;
-                chars.next(); // ]
-
-                stacks_map
-                    .entry(String::from(*stack_key))
-                    .or_insert(VecDeque::new());
-                if supply_crate.is_alphabetic() {
-                    // 'unwrap()' is suitable in this case as the above 'or_insert' ensures that the key exists.
-                    stacks_map
-                        .get_mut(&String::from(*stack_key))
-                        .unwrap()
-                        .push_front(String::from(supply_crate));
-                }
-                chars.next();
+        for x in 0..drawing.width() {
+            let column: Vec<char> = drawing.column(x).into_iter().flatten().copied().collect();
+            let Some((stack_key, supply_crates)) = column.split_last() else {
+                continue;
+            };
+            if stack_key.is_whitespace() {
+                continue;
             }
+            let stack = supply_crates
+                .iter()
+                .rev()
+                .filter(|supply_crate| supply_crate.is_alphabetic())
+                .map(|supply_crate| String::from(*supply_crate))
+                .collect();
+            stacks_map.insert(String::from(*stack_key), stack);
         }
 
+        if stacks_map.is_empty() {
+            return Err(SuppliesError::ParseFailure);
+        }
         Ok(Self { stacks_map })
     }
 
     fn move_crates_9000(&mut self, n: usize, src: &str, dst: &str) -> Result<(), SuppliesError> {
+        tracing::debug!(n, src, dst, "crane move");
         let src_stack = self
             .stacks_map
             .get_mut(src)
@@ -70,6 +87,7 @@ impl Supplies {
     }
 
     fn move_crates_9001(&mut self, n: usize, src: &str, dst: &str) -> Result<(), SuppliesError> {
+        tracing::debug!(n, src, dst, "crane move");
         let src_stack = self
             .stacks_map
             .get_mut(src)
@@ -96,36 +114,446 @@ impl Supplies {
 
         Ok(tops.join(""))
     }
+
+    fn sorted_keys(&self) -> Vec<String> {
+        let mut sorted_keys: Vec<String> = self.stacks_map.keys().cloned().collect();
+        sorted_keys.sort();
+        sorted_keys
+    }
+
+    fn sorted_crates(&self) -> Vec<&String> {
+        let mut crates: Vec<&String> = self.stacks_map.values().flatten().collect();
+        crates.sort();
+        crates
+    }
+
+    // Finds the fewest single-crate moves that turn these stacks into 'target', as (src, dst) pairs.
+    // This is an A* search over whole arrangements, so it is only practical for a handful of crates;
+    // 'max_expansions' bounds how long it may take before giving up with 'NoPlan'.
+    fn plan_rearrangement(
+        &self,
+        target: &Supplies,
+        max_expansions: Option<usize>,
+    ) -> Result<Vec<(String, String)>, SuppliesError> {
+        let keys = self.sorted_keys();
+        if keys != target.sorted_keys() || self.sorted_crates() != target.sorted_crates() {
+            return Err(SuppliesError::Unreachable);
+        }
+        let arrange = |supplies: &Supplies| -> Arrangement {
+            keys.iter()
+                .map(|key| supplies.stacks_map[key].clone())
+                .collect()
+        };
+        let goal = arrange(target);
+
+        let neighbours = |arrangement: &Arrangement| {
+            let mut next = Vec::new();
+            for src in 0..arrangement.len() {
+                for dst in 0..arrangement.len() {
+                    if src == dst || arrangement[src].is_empty() {
+                        continue;
+                    }
+                    let mut moved = arrangement.clone();
+                    let supply_crate = moved[src].pop_back().expect("non-empty supply stack");
+                    moved[dst].push_back(supply_crate);
+                    next.push((moved, 1));
+                }
+            }
+            next
+        };
+        // Every crate above the part of its stack that already matches the goal has to move at least once.
+        let misplaced = |arrangement: &Arrangement| -> u64 {
+            arrangement
+                .iter()
+                .zip(&goal)
+                .map(|(stack, goal_stack)| {
+                    let settled = stack
+                        .iter()
+                        .zip(goal_stack)
+                        .take_while(|(a, b)| a == b)
+                        .count();
+                    (stack.len() - settled) as u64
+                })
+                .sum()
+        };
+        let options = Options {
+            max_expansions,
+            ..Options::default()
+        };
+        let path = search::astar(
+            arrange(self),
+            neighbours,
+            misplaced,
+            |arrangement| *arrangement == goal,
+            options,
+        )
+        .ok_or(SuppliesError::NoPlan)?;
+
+        let moves = path
+            .states
+            .windows(2)
+            .map(|step| {
+                let (before, after) = (&step[0], &step[1]);
+                let src = (0..keys.len()).find(|&i| after[i].len() < before[i].len());
+                let dst = (0..keys.len()).find(|&i| after[i].len() > before[i].len());
+                let (src, dst) = src.zip(dst).expect("each step moves one crate");
+                (keys[src].clone(), keys[dst].clone())
+            })
+            .collect();
+        Ok(moves)
+    }
 }
 
-fn parse_instruction(instruction: &str) -> Result<(usize, String, String), ParseIntError> {
-    let mut words = instruction.split(' ');
-    let n = words.nth(1).unwrap().parse::<usize>()?;
-    let src = words.nth(1).unwrap();
-    let dst = words.nth(1).unwrap();
-    Ok((n, String::from(src), String::from(dst)))
+// Draws the stacks the same way the puzzle input does, tallest stack first.
+impl fmt::Display for Supplies {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut sorted_keys: Vec<&String> = self.stacks_map.keys().collect();
+        sorted_keys.sort();
+        let height = self
+            .stacks_map
+            .values()
+            .map(VecDeque::len)
+            .max()
+            .unwrap_or(0);
+
+        for level in (0..height).rev() {
+            let row: Vec<String> = sorted_keys
+                .iter()
+                .map(|key| match self.stacks_map[*key].get(level) {
+                    Some(supply_crate) => format!("[{supply_crate}]"),
+                    None => String::from("   "),
+                })
+                .collect();
+            writeln!(f, "{}", row.join(" ").trim_end())?;
+        }
+        let keys: Vec<String> = sorted_keys.iter().map(|key| format!(" {key} ")).collect();
+        write!(f, "{}", keys.join(" "))
+    }
 }
 
+#[tracing::instrument]
 pub fn part1(file_path: &str) -> String {
     let puzzle_input = fs::read_to_string(file_path).unwrap();
     let mut puzzle_split = puzzle_input.split("\n\n");
     let mut supplies = Supplies::try_from(puzzle_split.next().unwrap()).unwrap();
 
+    let _solve = tracing::info_span!("solve").entered();
     for instruction in puzzle_split.next().unwrap().split('\n') {
-        let (n, src, dst) = parse_instruction(instruction).unwrap();
-        supplies.move_crates_9000(n, &src, &dst).unwrap();
+        let (n, src, dst) = parse::move_instruction(instruction).unwrap();
+        supplies.move_crates_9000(n, src, dst).unwrap();
     }
     supplies.get_stack_tops().unwrap()
 }
 
+#[tracing::instrument]
 pub fn part2(file_path: &str) -> String {
     let puzzle_input = fs::read_to_string(file_path).unwrap();
     let mut puzzle_split = puzzle_input.split("\n\n");
     let mut supplies = Supplies::try_from(puzzle_split.next().unwrap()).unwrap();
 
+    let _solve = tracing::info_span!("solve").entered();
     for instruction in puzzle_split.next().unwrap().split('\n') {
-        let (n, src, dst) = parse_instruction(instruction).unwrap();
-        supplies.move_crates_9001(n, &src, &dst).unwrap();
+        let (n, src, dst) = parse::move_instruction(instruction).unwrap();
+        supplies.move_crates_9001(n, src, dst).unwrap();
     }
     supplies.get_stack_tops().unwrap()
 }
+
+pub fn explain(file_path: &str) -> Result<String, Box<dyn error::Error>> {
+    let puzzle_input = fs::read_to_string(file_path)?;
+    let (drawing, instructions) = puzzle_input
+        .split_once("\n\n")
+        .ok_or("Missing blank line between drawing and instructions")?;
+    let mut supplies_9000 = Supplies::try_from(drawing)?;
+    let mut supplies_9001 = Supplies::try_from(drawing)?;
+    let start = supplies_9000.to_string();
+
+    for instruction in instructions.split('\n') {
+        let (n, src, dst) = parse::move_instruction(instruction)?;
+        supplies_9000.move_crates_9000(n, src, dst)?;
+        supplies_9001.move_crates_9001(n, src, dst)?;
+    }
+    Ok(format!(
+        "Starting stacks:\n{start}\n\nAfter the CrateMover 9000:\n{supplies_9000}\n\nAfter the CrateMover 9001:\n{supplies_9001}"
+    ))
+}
+
+// Enough for the example and small hand-made drawings; full puzzle inputs are far beyond any search.
+const PLAN_LIMIT: usize = 100_000;
+
+// The drawing from 'Display' under a caption, with the top 'n' crates of stack 'key' highlighted.
+fn supplies_frame(supplies: &Supplies, caption: &str, moved: Option<(&str, usize)>) -> Frame {
+    let mut frame = Frame::from_text(&format!("{caption}\n{supplies}"));
+    if let Some((key, n)) = moved {
+        let mut sorted_keys: Vec<&String> = supplies.stacks_map.keys().collect();
+        sorted_keys.sort();
+        let column = sorted_keys.iter().position(|sorted_key| *sorted_key == key);
+        let height = supplies
+            .stacks_map
+            .values()
+            .map(VecDeque::len)
+            .max()
+            .unwrap_or(0);
+        let stack_len = supplies.stacks_map.get(key).map_or(0, VecDeque::len);
+        if let Some(column) = column {
+            for level in stack_len.saturating_sub(n)..stack_len {
+                // One line for the caption, then the tallest stack's top level first.
+                let y = 1 + height - 1 - level;
+                for x in 4 * column..4 * column + 3 {
+                    frame.paint(Point::new(x, y), Colour::Yellow);
+                }
+            }
+        }
+    }
+    frame
+}
+
+// Animates the CrateMover 9000 working through the instructions, highlighting the crates just moved.
+pub fn visualize(file_path: &str, sink: &mut dyn viz::Sink) -> Result<(), Box<dyn error::Error>> {
+    let puzzle_input = fs::read_to_string(file_path)?;
+    let (drawing, instructions) = puzzle_input
+        .split_once("\n\n")
+        .ok_or("Missing blank line between drawing and instructions")?;
+    let mut supplies = Supplies::try_from(drawing)?;
+    sink.frame(&supplies_frame(&supplies, "Starting stacks", None))?;
+
+    for instruction in instructions.lines().filter(|line| !line.is_empty()) {
+        let (n, src, dst) = parse::move_instruction(instruction)?;
+        supplies.move_crates_9000(n, src, dst)?;
+        sink.frame(&supplies_frame(&supplies, instruction, Some((dst, n))))?;
+    }
+    Ok(())
+}
+
+pub(crate) struct Explorer {
+    start: Supplies,
+    supplies: Supplies,
+    instructions: Vec<(usize, String, String)>,
+    // The next puzzle instruction 'step' applies; restored together with the stacks on 'undo'.
+    next_instruction: usize,
+    crane_9001: bool,
+    history: Vec<(Supplies, usize)>,
+}
+
+impl Explorer {
+    pub(crate) fn new(file_path: &str) -> Result<Self, Box<dyn error::Error>> {
+        Self::parse(&fs::read_to_string(file_path)?)
+    }
+
+    fn parse(puzzle_input: &str) -> Result<Self, Box<dyn error::Error>> {
+        let (drawing, instructions) = puzzle_input
+            .split_once("\n\n")
+            .ok_or("Missing blank line between drawing and instructions")?;
+        let instructions = instructions
+            .split('\n')
+            .filter(|line| !line.is_empty())
+            .map(|line| {
+                parse::move_instruction(line)
+                    .map(|(n, src, dst)| (n, String::from(src), String::from(dst)))
+            })
+            .collect::<Result<_, _>>()?;
+        let start = Supplies::try_from(drawing)?;
+        Ok(Self {
+            supplies: start.clone(),
+            start,
+            instructions,
+            next_instruction: 0,
+            crane_9001: false,
+            history: Vec::new(),
+        })
+    }
+
+    // The crane methods panic on a short stack (see 'move_crates_9000'), so user-typed moves are
+    // checked here before they reach them.
+    fn apply(&mut self, n: usize, src: &str, dst: &str) -> Result<(), Box<dyn error::Error>> {
+        let available = self
+            .supplies
+            .stacks_map
+            .get(src)
+            .ok_or(SuppliesError::MissingKey(String::from(src)))?
+            .len();
+        if available < n {
+            return Err(format!("Stack {src} only holds {available} crate(s).").into());
+        }
+        if !self.supplies.stacks_map.contains_key(dst) {
+            return Err(SuppliesError::MissingKey(String::from(dst)).into());
+        }
+        if self.crane_9001 {
+            self.supplies.move_crates_9001(n, src, dst)?;
+        } else {
+            self.supplies.move_crates_9000(n, src, dst)?;
+        }
+        Ok(())
+    }
+
+    // The stacks the puzzle instructions end with, given the crane model.
+    fn final_supplies(&self, crane_9001: bool) -> Result<Supplies, Box<dyn error::Error>> {
+        let mut supplies = self.start.clone();
+        for (n, src, dst) in &self.instructions {
+            let available = supplies.stacks_map.get(src).map_or(0, VecDeque::len);
+            if available < *n {
+                return Err(format!("Stack {src} only holds {available} crate(s).").into());
+            }
+            if crane_9001 {
+                supplies.move_crates_9001(*n, src, dst)?;
+            } else {
+                supplies.move_crates_9000(*n, src, dst)?;
+            }
+        }
+        Ok(supplies)
+    }
+}
+
+impl explore::Explorer for Explorer {
+    fn execute(&mut self, command: &str) -> Result<String, Box<dyn error::Error>> {
+        let words: Vec<&str> = command.split_whitespace().collect();
+        match words.as_slice() {
+            ["show"] | ["show", "stacks"] => Ok(self.supplies.to_string()),
+            ["tops"] => Ok(self.supplies.get_stack_tops()?),
+            ["crane"] => Ok(String::from(if self.crane_9001 { "9001" } else { "9000" })),
+            ["crane", "9000"] => {
+                self.crane_9001 = false;
+                Ok(String::new())
+            }
+            ["crane", "9001"] => {
+                self.crane_9001 = true;
+                Ok(String::new())
+            }
+            ["move", ..] => {
+                let (n, src, dst) = parse::move_instruction(command)?;
+                let snapshot = (self.supplies.clone(), self.next_instruction);
+                self.apply(n, src, dst)?;
+                self.history.push(snapshot);
+                Ok(self.supplies.to_string())
+            }
+            ["step"] | ["step", _] => {
+                let count = match words.get(1) {
+                    Some(count) => count.parse::<usize>()?,
+                    None => 1,
+                };
+                let snapshot = (self.supplies.clone(), self.next_instruction);
+                let mut applied = Vec::new();
+                for _ in 0..count {
+                    let Some((n, src, dst)) = self.instructions.get(self.next_instruction).cloned()
+                    else {
+                        break;
+                    };
+                    if let Err(e) = self.apply(n, &src, &dst) {
+                        (self.supplies, self.next_instruction) = snapshot;
+                        return Err(e);
+                    }
+                    applied.push(format!("move {n} from {src} to {dst}"));
+                    self.next_instruction += 1;
+                }
+                if applied.is_empty() {
+                    return Err("No puzzle instructions left.".into());
+                }
+                self.history.push(snapshot);
+                Ok(format!("{}\n{}", applied.join("\n"), self.supplies))
+            }
+            ["plan"] | ["plan", "9000"] | ["plan", "9001"] => {
+                let crane_9001 = match words.get(1) {
+                    Some(model) => *model == "9001",
+                    None => self.crane_9001,
+                };
+                let target = self.final_supplies(crane_9001)?;
+                let moves = self
+                    .supplies
+                    .plan_rearrangement(&target, Some(PLAN_LIMIT))?;
+                let mut lines: Vec<String> = moves
+                    .iter()
+                    .map(|(src, dst)| format!("move 1 from {src} to {dst}"))
+                    .collect();
+                let puzzle_moves: usize = self.instructions.iter().map(|(n, _, _)| n).sum();
+                lines.push(format!(
+                    "{} single-crate move(s); the puzzle's instructions move {puzzle_moves} crate(s).",
+                    moves.len()
+                ));
+                Ok(lines.join("\n"))
+            }
+            ["undo"] => {
+                let (supplies, next_instruction) = self.history.pop().ok_or("Nothing to undo.")?;
+                self.supplies = supplies;
+                self.next_instruction = next_instruction;
+                Ok(self.supplies.to_string())
+            }
+            // Solved from the loaded starting stacks rather than by 'part1'/'part2', which panic on bad input.
+            ["part1"] => Ok(self.final_supplies(false)?.get_stack_tops()?),
+            ["part2"] => Ok(self.final_supplies(true)?.get_stack_tops()?),
+            _ => Err(format!("Unknown command: {command}").into()),
+        }
+    }
+
+    fn help(&self) -> &'static str {
+        "show stacks              draw the current stacks
+tops                     crates on top of each stack
+move N from A to B       move crates with the current crane
+crane [9000|9001]        show or switch the crane model
+step [N]                 apply the next N puzzle instructions (default 1)
+plan [9000|9001]         fewest single-crate moves from here to the puzzle's final stacks
+undo                     revert the last move or step
+part1 | part2            solve the loaded puzzle from its starting stacks
+quit                     leave the explorer"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DRAWING: &str = "    [D]    
+[N] [C]    
+[Z] [M] [P]
+ 1   2   3 ";
+
+    #[test]
+    fn test_plan_rearrangement() {
+        let start = Supplies::try_from(DRAWING).unwrap();
+        let mut target = start.clone();
+        for (n, src, dst) in [(1, "2", "1"), (3, "1", "3"), (2, "2", "1"), (1, "1", "2")] {
+            target.move_crates_9000(n, src, dst).unwrap();
+        }
+
+        let moves = start.plan_rearrangement(&target, None).unwrap();
+        let mut replayed = start.clone();
+        for (src, dst) in &moves {
+            replayed.move_crates_9000(1, src, dst).unwrap();
+        }
+        assert_eq!(replayed.to_string(), target.to_string());
+        // The puzzle's own instructions move seven crates.
+        assert_eq!(moves.len(), 4);
+        assert_eq!(start.plan_rearrangement(&start, None).unwrap(), vec![]);
+
+        let mut missing = start.clone();
+        missing.stacks_map.get_mut("3").unwrap().clear();
+        assert!(matches!(
+            start.plan_rearrangement(&missing, None),
+            Err(SuppliesError::Unreachable)
+        ));
+    }
+
+    #[test]
+    fn test_explorer() {
+        use explore::Explorer as _;
+
+        // With the trailing newline that 'part1' and 'part2' themselves trip over.
+        let input = format!("{DRAWING}\n\nmove 1 from 2 to 1\nmove 3 from 1 to 3\nmove 2 from 2 to 1\nmove 1 from 1 to 2\n");
+        let mut explorer = Explorer::parse(&input).unwrap();
+        assert_eq!(explorer.execute("part1").unwrap(), "CMZ");
+        assert_eq!(explorer.execute("part2").unwrap(), "MCD");
+        assert_eq!(explorer.execute("tops").unwrap(), "NDP");
+
+        assert!(explorer.execute("step 1").is_ok());
+        assert_eq!(explorer.execute("tops").unwrap(), "DCP");
+        explorer.execute("undo").unwrap();
+        assert_eq!(explorer.execute("tops").unwrap(), "NDP");
+        assert!(explorer.execute("undo").is_err());
+
+        assert!(explorer.execute("move 4 from 3 to 1").is_err());
+        assert!(explorer.execute("move 1 from 9 to 1").is_err());
+        assert!(explorer.execute("step many").is_err());
+        assert!(explorer.execute("crates").is_err());
+        // Failed commands leave the stacks alone.
+        assert_eq!(explorer.execute("tops").unwrap(), "NDP");
+    }
+}
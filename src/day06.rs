@@ -1,33 +1,90 @@
-use std::collections::{HashSet, VecDeque};
-use std::fs;
+use std::error;
 
-fn find_marker_end(puzzle_input: &str, marker_size: usize) -> Option<u32> {
-    let mut marker_window = VecDeque::new();
-    for (idx, character) in puzzle_input.chars().enumerate() {
-        marker_window.push_front(character);
-        if marker_window.len() > marker_size {
-            marker_window.pop_back().unwrap();
-            if is_unique(&marker_window) {
-                return Some(idx as u32 + 1);
-            }
+use crate::grid::Point;
+use crate::input;
+use crate::viz::{self, Colour, Frame};
+
+// A single pass over the datastream that only remembers where each byte value was last seen,
+// so memory stays constant however long the datastream is.
+// The window of distinct bytes ending at 'idx' starts just after the latest repeat of any byte in it.
+fn find_marker_end(datastream: impl AsRef<[u8]>, marker_size: usize) -> Option<usize> {
+    let mut next_after_last_seen = [0usize; 256];
+    let mut window_start = 0;
+    for (idx, &byte) in datastream.as_ref().iter().enumerate() {
+        window_start = window_start.max(next_after_last_seen[byte as usize]);
+        next_after_last_seen[byte as usize] = idx + 1;
+
+        let unique = idx + 1 - window_start >= marker_size;
+        tracing::trace!(idx, unique, "marker check");
+        if unique {
+            return Some(idx + 1);
         }
     }
     None
 }
 
-fn is_unique(marker_window: &VecDeque<char>) -> bool {
-    let marker_set: HashSet<_> = marker_window.iter().collect();
-    marker_window.len() == marker_set.len()
+// There is nothing to parse beyond mapping the datastream, which is what the 'parse' span covers.
+#[tracing::instrument]
+pub fn part1(file_path: &str) -> usize {
+    let datastream = {
+        let _parse = tracing::info_span!("parse").entered();
+        input::map(file_path).unwrap()
+    };
+    let _solve = tracing::info_span!("solve").entered();
+    find_marker_end(&datastream, 4).unwrap()
 }
 
-pub fn part1(file_path: &str) -> u32 {
-    let puzzle_input = fs::read_to_string(file_path).unwrap();
-    find_marker_end(&puzzle_input, 4).unwrap()
+#[tracing::instrument]
+pub fn part2(file_path: &str) -> usize {
+    let datastream = {
+        let _parse = tracing::info_span!("parse").entered();
+        input::map(file_path).unwrap()
+    };
+    let _solve = tracing::info_span!("solve").entered();
+    find_marker_end(&datastream, 14).unwrap()
 }
 
-pub fn part2(file_path: &str) -> u32 {
-    let puzzle_input = fs::read_to_string(file_path).unwrap();
-    find_marker_end(&puzzle_input, 14).unwrap()
+// How much of the datastream each frame shows around the window.
+const VIEW_WIDTH: usize = 48;
+
+// Slides the start-of-packet window along the datastream: repeated bytes in the window are red,
+// and the window turns green once it finds the marker.
+pub fn visualize(file_path: &str, sink: &mut dyn viz::Sink) -> Result<(), Box<dyn error::Error>> {
+    let marker_size = 4;
+    let datastream = input::map(file_path)?;
+    let end =
+        find_marker_end(&datastream, marker_size).ok_or("No start-of-packet marker found.")?;
+
+    for window_end in marker_size..=end {
+        let window_start = window_end - marker_size;
+        let view_start = window_start.saturating_sub(VIEW_WIDTH / 2);
+        let view_end = datastream.len().min(view_start + VIEW_WIDTH);
+        let view: String = datastream[view_start..view_end]
+            .iter()
+            .map(|&byte| {
+                if byte.is_ascii_graphic() {
+                    char::from(byte)
+                } else {
+                    '.'
+                }
+            })
+            .collect();
+
+        let mut frame = Frame::from_text(&format!("Characters processed: {window_end}\n{view}"));
+        let window = &datastream[window_start..window_end];
+        for (offset, byte) in window.iter().enumerate() {
+            let colour = if window_end == end {
+                Colour::Green
+            } else if window.iter().filter(|other| *other == byte).count() > 1 {
+                Colour::Red
+            } else {
+                Colour::Yellow
+            };
+            frame.paint(Point::new(window_start - view_start + offset, 1), colour);
+        }
+        sink.frame(&frame)?;
+    }
+    Ok(())
 }
 
 #[cfg(test)]
@@ -77,4 +134,10 @@ mod tests {
         );
         assert_eq!(find_marker_end("zcfzfwzzqfrljwz", marker_size), None);
     }
+
+    #[test]
+    fn test_marker_at_start() {
+        assert_eq!(find_marker_end("abcdaaaa", 4).unwrap(), 4);
+        assert_eq!(find_marker_end("", 4), None);
+    }
 }
@@ -4,6 +4,10 @@ use std::error;
 use std::fs;
 use std::rc::Rc;
 
+use crate::arith::{Accumulator, OverflowError};
+use crate::explore;
+use crate::parse::{self, TerminalLine};
+
 type DirectoryHandle = Rc<RefCell<Directory>>;
 
 struct FileSystem {
@@ -20,7 +24,13 @@ struct Directory {
 
 struct File {
     name: String,
-    size: usize,
+    size: u64,
+}
+
+#[derive(Debug, thiserror::Error)]
+enum FileSystemError {
+    #[error("No such directory: {0}")]
+    NoSuchDirectory(String),
 }
 
 impl FileSystem {
@@ -35,14 +45,23 @@ impl FileSystem {
         Self { root, current }
     }
 
-    fn cd(&mut self, trgt: &str) {
+    fn cd(&mut self, trgt: &str) -> Result<(), FileSystemError> {
+        tracing::debug!(directory = trgt, "cd");
+        let missing = || FileSystemError::NoSuchDirectory(String::from(trgt));
         self.current = match trgt {
             "/" => Rc::clone(&self.root),
-            // unwrap consumes `self` which means the value `Option` is moved; a dereferenced value cannot be moved
-            // as_ref casts `Option<DirectoryHandle>` to `Option<&DirectoryHandle>`
-            ".." => Rc::clone(self.current.borrow().parent.as_ref().unwrap()),
-            _ => Rc::clone(self.current.borrow().children.get(trgt).unwrap()),
-        }
+            // as_ref casts `Option<DirectoryHandle>` to `Option<&DirectoryHandle>`, so the handle is cloned
+            // rather than moved out of the borrowed directory
+            ".." => Rc::clone(self.current.borrow().parent.as_ref().ok_or_else(missing)?),
+            _ => Rc::clone(
+                self.current
+                    .borrow()
+                    .children
+                    .get(trgt)
+                    .ok_or_else(missing)?,
+            ),
+        };
+        Ok(())
     }
 
     fn mkdir(&mut self, trgt: &str) {
@@ -58,7 +77,7 @@ impl FileSystem {
             })));
     }
 
-    fn add_file(&mut self, trgt: &str, size: usize) {
+    fn add_file(&mut self, trgt: &str, size: u64) {
         let name = String::from(trgt);
         self.current
             .borrow_mut()
@@ -66,95 +85,246 @@ impl FileSystem {
             .insert(name.clone(), File { name, size });
     }
 
-    #[allow(dead_code)]
-    fn debug(directory: &DirectoryHandle) {
-        Self::debug_recursive(directory, 0);
+    fn tree(directory: &DirectoryHandle) -> String {
+        let mut output = String::new();
+        Self::tree_recursive(directory, 0, &mut output);
+        output
     }
 
-    #[allow(dead_code)]
-    fn debug_recursive(directory: &DirectoryHandle, indent: usize) {
-        print!("{}", &" ".repeat(indent));
-        println!("({})", directory.borrow().name);
-        for (_, file) in directory.borrow().files.iter() {
-            println!("{}{} {}", &" ".repeat(indent + 4), file.name, file.size);
+    // Entries are sorted by name so the output is stable across runs ('HashMap' order is not).
+    fn tree_recursive(directory: &DirectoryHandle, indent: usize, output: &mut String) {
+        let directory = directory.borrow();
+        output.push_str(&" ".repeat(indent));
+        output.push_str(&format!("({})\n", directory.name));
+
+        let mut files: Vec<&File> = directory.files.values().collect();
+        files.sort_by(|a, b| a.name.cmp(&b.name));
+        for file in files {
+            output.push_str(&format!(
+                "{}{} {}\n",
+                &" ".repeat(indent + 4),
+                file.name,
+                file.size
+            ));
         }
-        for (_, sub_directory) in directory.borrow().children.iter() {
-            Self::debug_recursive(sub_directory, indent + 4);
+
+        let mut children: Vec<(&String, &DirectoryHandle)> = directory.children.iter().collect();
+        children.sort_by(|a, b| a.0.cmp(b.0));
+        for (_, sub_directory) in children {
+            Self::tree_recursive(sub_directory, indent + 4, output);
         }
     }
 }
 
-mod puzzle_parser {
-    use nom::bytes::complete::{tag, take_while};
-    use nom::character::complete::{alphanumeric1, multispace1};
-    use nom::combinator::map_res;
-    use nom::sequence::preceded;
-    use nom::IResult;
-
-    pub(crate) fn parse_cd(line: &str) -> Option<&str> {
-        let result: IResult<_, _> = tag("$ cd ")(line);
-        result.ok().map(|(directory, _)| directory)
+#[tracing::instrument(name = "parse", skip_all)]
+fn construct_filesystem(terminal_output: &str) -> Result<FileSystem, Box<dyn error::Error>> {
+    let mut filesystem = FileSystem::new();
+    for (idx, line) in terminal_output.lines().enumerate() {
+        match parse::terminal_line(line).map_err(|e| e.at_line(idx + 1))? {
+            TerminalLine::Cd(directory) => filesystem
+                .cd(directory)
+                .map_err(|e| format!("line {}: {e}", idx + 1))?,
+            TerminalLine::Ls => continue,
+            TerminalLine::Dir(directory) => filesystem.mkdir(directory),
+            TerminalLine::File(size, file) => filesystem.add_file(file, size),
+        }
     }
+    Ok(filesystem)
+}
 
-    pub(crate) fn parse_ls(line: &str) -> Option<&str> {
-        let result: IResult<_, _> = tag("$ ls")(line);
-        result.ok().map(|(_, empty)| empty)
+#[tracing::instrument(name = "solve", skip_all)]
+fn calc_size(
+    filesystem: &FileSystem,
+    predicate: impl Fn(&&u64) -> bool,
+) -> Result<u64, OverflowError> {
+    let mut memory = Vec::new();
+    record_sizes_recursive(&filesystem.root, &mut memory)?;
+    let mut total = Accumulator::new("size total");
+    for size in memory.iter().filter(predicate) {
+        total.add(*size, None)?;
     }
+    Ok(total.total())
+}
 
-    pub(crate) fn parse_directory(line: &str) -> Option<&str> {
-        let result: IResult<_, _> = preceded(tag("dir "), alphanumeric1)(line);
-        result.ok().map(|(_, directory)| directory)
+fn record_sizes_recursive(
+    current: &DirectoryHandle,
+    memory: &mut Vec<u64>,
+) -> Result<u64, OverflowError> {
+    let mut total_size = Accumulator::new("directory size");
+    for file in current.borrow().files.values() {
+        total_size.add(file.size, None)?;
     }
+    for (_, dir) in current.borrow().children.iter() {
+        total_size.add(record_sizes_recursive(dir, memory)?, None)?;
+    }
+    memory.push(total_size.total());
+    Ok(total_size.total())
+}
 
-    pub(crate) fn parse_file(line: &str) -> Option<(usize, &str)> {
-        let result: IResult<_, _> = map_res(take_while(is_digit), str::parse)(line);
-        if let Some((line, size)) = result.ok() {
-            let result: IResult<_, _> = multispace1(line);
-            return result.ok().map(|(file_name, _)| (size, file_name));
-        }
-        None
+// Part 1 totals the directories of at most this size.
+const SMALL_DIRECTORY: fn(&&u64) -> bool = |&size| *size <= 100_000;
+
+#[tracing::instrument]
+pub fn part1(file_path: &str) -> Result<u64, Box<dyn error::Error>> {
+    let puzzle_input = fs::read_to_string(file_path)?;
+    let filesystem = construct_filesystem(&puzzle_input)?;
+    Ok(calc_size(&filesystem, SMALL_DIRECTORY)?)
+}
+
+pub fn explain(file_path: &str) -> Result<String, Box<dyn error::Error>> {
+    let puzzle_input = fs::read_to_string(file_path)?;
+    let filesystem = construct_filesystem(&puzzle_input)?;
+    Ok(FileSystem::tree(&filesystem.root))
+}
+
+pub(crate) struct Explorer {
+    filesystem: FileSystem,
+    // Previous working directories, so 'undo' can step back through 'cd' commands.
+    history: Vec<DirectoryHandle>,
+}
+
+impl Explorer {
+    pub(crate) fn new(file_path: &str) -> Result<Self, Box<dyn error::Error>> {
+        Self::parse(&fs::read_to_string(file_path)?)
     }
 
-    fn is_digit(c: char) -> bool {
-        c.is_ascii_digit()
+    fn parse(puzzle_input: &str) -> Result<Self, Box<dyn error::Error>> {
+        let mut filesystem = construct_filesystem(puzzle_input)?;
+        filesystem.cd("/")?;
+        Ok(Self {
+            filesystem,
+            history: Vec::new(),
+        })
     }
-}
 
-fn construct_filesystem(terminal_output: &str) -> FileSystem {
-    let mut filesystem = FileSystem::new();
-    for line in terminal_output.split('\n') {
-        if let Some(directory) = puzzle_parser::parse_cd(line) {
-            filesystem.cd(directory);
-        } else if puzzle_parser::parse_ls(line).is_some() {
-            continue;
-        } else if let Some(directory) = puzzle_parser::parse_directory(line) {
-            filesystem.mkdir(directory);
-        } else if let Some((size, file)) = puzzle_parser::parse_file(line) {
-            filesystem.add_file(file, size);
-        } else {
-            panic!("TODO")
+    fn pwd(&self) -> String {
+        let mut names = Vec::new();
+        let mut current = Some(Rc::clone(&self.filesystem.current));
+        while let Some(directory) = current {
+            if directory.borrow().parent.is_some() {
+                names.push(directory.borrow().name.clone());
+            }
+            current = directory.borrow().parent.as_ref().map(Rc::clone);
         }
+        names.reverse();
+        format!("/{}", names.join("/"))
     }
-    filesystem
 }
 
-fn calc_size(filesystem: FileSystem, predicate: impl Fn(&&usize) -> bool) -> usize {
-    let mut memory = Vec::new();
-    record_sizes_recursive(&filesystem.root, &mut memory);
-    memory.iter().filter(predicate).sum()
-}
+impl explore::Explorer for Explorer {
+    fn execute(&mut self, command: &str) -> Result<String, Box<dyn error::Error>> {
+        let words: Vec<&str> = command.split_whitespace().collect();
+        match words.as_slice() {
+            ["pwd"] => Ok(self.pwd()),
+            ["ls"] => {
+                let current = self.filesystem.current.borrow();
+                let mut entries: Vec<String> = current
+                    .children
+                    .keys()
+                    .map(|name| format!("dir {name}"))
+                    .chain(
+                        current
+                            .files
+                            .values()
+                            .map(|f| format!("{} {}", f.size, f.name)),
+                    )
+                    .collect();
+                entries.sort();
+                Ok(entries.join("\n"))
+            }
+            ["cd", target] => {
+                let previous = Rc::clone(&self.filesystem.current);
+                self.filesystem.cd(target)?;
+                self.history.push(previous);
+                Ok(self.pwd())
+            }
+            ["du"] => {
+                let mut sizes = Vec::new();
+                let total = record_sizes_recursive(&self.filesystem.current, &mut sizes)?;
+                let current = self.filesystem.current.borrow();
+                let mut children: Vec<(&String, &DirectoryHandle)> =
+                    current.children.iter().collect();
+                children.sort_by(|a, b| a.0.cmp(b.0));
+                let mut lines = Vec::new();
+                for (name, child) in children {
+                    let size = record_sizes_recursive(child, &mut Vec::new())?;
+                    lines.push(format!("{size} {name}"));
+                }
+                lines.push(format!("{total} ."));
+                Ok(lines.join("\n"))
+            }
+            ["tree"] => Ok(FileSystem::tree(&self.filesystem.current)
+                .trim_end()
+                .to_string()),
+            ["undo"] => {
+                self.filesystem.current = self.history.pop().ok_or("Nothing to undo.")?;
+                Ok(self.pwd())
+            }
+            // Solved from the loaded filesystem, whichever directory the explorer is in.
+            ["part1"] => Ok(calc_size(&self.filesystem, SMALL_DIRECTORY)?.to_string()),
+            _ => Err(format!("Unknown command: {command}").into()),
+        }
+    }
 
-fn record_sizes_recursive(current: &DirectoryHandle, memory: &mut Vec<usize>) -> usize {
-    let mut total_size: usize = current.borrow().files.values().map(|f| f.size).sum();
-    for (_, dir) in current.borrow().children.iter() {
-        total_size += record_sizes_recursive(dir, memory);
+    fn help(&self) -> &'static str {
+        "pwd                      print the current directory
+ls                       list the current directory
+cd DIR | .. | /          change directory
+du                       total size of each subdirectory and of the current directory
+tree                     files and directories below the current directory
+undo                     go back to the previous directory
+part1                    solve the loaded filesystem
+quit                     leave the explorer"
     }
-    memory.push(total_size);
-    total_size
 }
 
-pub fn part1(file_path: &str) -> Result<usize, Box<dyn error::Error>> {
-    let puzzle_input = fs::read_to_string(file_path)?;
-    let filesystem = construct_filesystem(&puzzle_input);
-    Ok(calc_size(filesystem, |&x| *x <= 100_000))
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::explore::Explorer as _;
+
+    const TERMINAL: &str = "$ cd /
+$ ls
+dir a
+14848514 b.txt
+8504156 c.dat
+dir d
+$ cd a
+$ ls
+dir e
+29116 f
+2557 g
+62596 h.lst
+$ cd e
+$ ls
+584 i
+$ cd ..
+$ cd ..
+$ cd d
+$ ls
+4060174 j
+8033020 d.log
+5626152 d.ext
+7214296 k
+";
+
+    #[test]
+    fn test_explorer() {
+        let mut explorer = Explorer::parse(TERMINAL).unwrap();
+        assert_eq!(explorer.execute("part1").unwrap(), "95437");
+        assert_eq!(explorer.execute("cd a").unwrap(), "/a");
+        assert_eq!(explorer.execute("du").unwrap(), "584 e\n94853 .");
+        // The answer does not depend on the working directory.
+        assert_eq!(explorer.execute("part1").unwrap(), "95437");
+        assert!(explorer.execute("cd nowhere").is_err());
+        assert_eq!(explorer.execute("pwd").unwrap(), "/a");
+        assert_eq!(explorer.execute("undo").unwrap(), "/");
+        assert!(explorer.execute("cd ..").is_err());
+        assert!(explorer.execute("rm -rf").is_err());
+
+        let Err(e) = Explorer::parse("$ cd /\n$ cd missing\n") else {
+            panic!("'cd' into an unlisted directory should fail");
+        };
+        assert_eq!(e.to_string(), "line 2: No such directory: missing");
+    }
 }
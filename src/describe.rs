@@ -0,0 +1,313 @@
+use crate::fetch;
+
+#[derive(Debug, thiserror::Error)]
+pub(crate) enum DescribeError {
+    #[error("No puzzle description (<article class=\"day-desc\">) found in the page.")]
+    MissingArticle,
+}
+
+// Puzzle pages are small and machine-written, so a flat scan over tags is enough:
+// no attribute other than 'href' matters and nothing relies on nesting being checked.
+#[derive(Debug, PartialEq)]
+enum Token<'a> {
+    Text(&'a str),
+    // Tag name and the raw attribute text.
+    Open(&'a str, &'a str),
+    Close(&'a str),
+}
+
+fn tokenize(html: &str) -> Vec<Token<'_>> {
+    let mut tokens = Vec::new();
+    let mut rest = html;
+    while !rest.is_empty() {
+        let Some(start) = rest.find('<') else {
+            tokens.push(Token::Text(rest));
+            break;
+        };
+        if start > 0 {
+            tokens.push(Token::Text(&rest[..start]));
+        }
+        let Some(end) = rest[start..].find('>') else {
+            tokens.push(Token::Text(&rest[start..]));
+            break;
+        };
+        let tag = rest[start + 1..start + end].trim_end_matches('/').trim();
+        match tag.strip_prefix('/') {
+            Some(name) => tokens.push(Token::Close(name.trim())),
+            None => {
+                let (name, attributes) = tag.split_once(char::is_whitespace).unwrap_or((tag, ""));
+                tokens.push(Token::Open(name, attributes));
+            }
+        }
+        rest = &rest[start + end + 1..];
+    }
+    tokens
+}
+
+fn attribute<'a>(attributes: &'a str, name: &str) -> Option<&'a str> {
+    let start = attributes.find(&format!("{name}=\""))? + name.len() + 2;
+    let length = attributes[start..].find('"')?;
+    Some(&attributes[start..start + length])
+}
+
+fn decode_entities(text: &str) -> String {
+    let mut decoded = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find('&') {
+        decoded.push_str(&rest[..start]);
+        rest = &rest[start..];
+        let entity = rest.find(';').map(|end| (&rest[1..end], end));
+        let character = entity.and_then(|(name, _)| match name {
+            "lt" => Some('<'),
+            "gt" => Some('>'),
+            "amp" => Some('&'),
+            "quot" => Some('"'),
+            "apos" => Some('\''),
+            _ => name
+                .strip_prefix('#')
+                .and_then(|code| code.parse().ok())
+                .and_then(char::from_u32),
+        });
+        match (character, entity) {
+            (Some(character), Some((_, end))) => {
+                decoded.push(character);
+                rest = &rest[end + 1..];
+            }
+            _ => {
+                decoded.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    decoded.push_str(rest);
+    decoded
+}
+
+// The tokens of each '<article class="day-desc">' (part 1, and part 2 once it is unlocked).
+fn articles<'a>(tokens: &'a [Token<'a>]) -> Vec<&'a [Token<'a>]> {
+    let mut articles = Vec::new();
+    let mut start = None;
+    for (i, token) in tokens.iter().enumerate() {
+        match token {
+            Token::Open("article", attributes)
+                if attribute(attributes, "class") == Some("day-desc") =>
+            {
+                start = Some(i + 1);
+            }
+            Token::Close("article") => {
+                if let Some(start) = start.take() {
+                    articles.push(&tokens[start..i]);
+                }
+            }
+            _ => {}
+        }
+    }
+    articles
+}
+
+#[derive(Default)]
+struct Markdown {
+    text: String,
+    in_pre: bool,
+    in_code: bool,
+    // Where the current heading's text starts, so its '--- ... ---' decoration can be dropped.
+    heading: Option<usize>,
+    links: Vec<String>,
+}
+
+impl Markdown {
+    // Outside '<pre>', runs of whitespace become one space and none are left at the start of a line.
+    fn push_text(&mut self, text: &str) {
+        let text = decode_entities(text);
+        if self.in_pre {
+            self.text.push_str(&text);
+            return;
+        }
+        for (i, word) in text.split_whitespace().enumerate() {
+            let spaced = i > 0 || text.starts_with(char::is_whitespace);
+            if spaced && !self.text.is_empty() && !self.text.ends_with([' ', '\n', '[', '`']) {
+                self.text.push(' ');
+            }
+            self.text.push_str(word);
+        }
+        if text.ends_with(char::is_whitespace) && !text.trim().is_empty() {
+            self.text.push(' ');
+        }
+    }
+
+    fn end_block(&mut self) {
+        let trimmed = self.text.trim_end_matches(' ').len();
+        self.text.truncate(trimmed);
+        self.text.push_str("\n\n");
+    }
+
+    fn push(&mut self, token: &Token) {
+        match token {
+            Token::Text(text) => self.push_text(text),
+            Token::Open("h2", _) => {
+                self.text.push_str("## ");
+                self.heading = Some(self.text.len());
+            }
+            Token::Close("h2") => {
+                if let Some(start) = self.heading.take() {
+                    let title = self.text[start..]
+                        .trim()
+                        .trim_matches('-')
+                        .trim()
+                        .to_string();
+                    self.text.truncate(start);
+                    self.text.push_str(&title);
+                }
+                self.end_block();
+            }
+            Token::Close("p") => self.end_block(),
+            Token::Open("pre", _) => {
+                self.text.push_str("```\n");
+                self.in_pre = true;
+            }
+            Token::Close("pre") => {
+                if !self.text.ends_with('\n') {
+                    self.text.push('\n');
+                }
+                self.text.push_str("```");
+                self.in_pre = false;
+                self.end_block();
+            }
+            Token::Open("code", _) if !self.in_pre => {
+                self.text.push('`');
+                self.in_code = true;
+            }
+            Token::Close("code") if !self.in_pre => {
+                self.text.push('`');
+                self.in_code = false;
+            }
+            Token::Open("em", _) | Token::Close("em") if !self.in_pre && !self.in_code => {
+                self.text.push('*');
+            }
+            Token::Open("a", attributes) => {
+                self.text.push('[');
+                self.links.push(fetch::absolute_url(
+                    attribute(attributes, "href").unwrap_or(""),
+                ));
+            }
+            Token::Close("a") => {
+                let href = self.links.pop().unwrap_or_default();
+                self.text.push_str(&format!("]({href})"));
+            }
+            Token::Open("li", _) => self.text.push_str("- "),
+            Token::Close("li") => {
+                let trimmed = self.text.trim_end().len();
+                self.text.truncate(trimmed);
+                self.text.push('\n');
+            }
+            Token::Close("ul") => self.text.push('\n'),
+            // Anything else ('span' titles, 'br', ...) only contributes its text.
+            _ => {}
+        }
+    }
+}
+
+// Converts the puzzle description(s) of a saved or downloaded puzzle page to Markdown.
+pub(crate) fn to_markdown(html: &str) -> Result<String, DescribeError> {
+    let tokens = tokenize(html);
+    let articles = articles(&tokens);
+    if articles.is_empty() {
+        return Err(DescribeError::MissingArticle);
+    }
+    let mut markdown = Markdown::default();
+    for article in articles {
+        for token in article {
+            markdown.push(token);
+        }
+    }
+    Ok(format!("{}\n", markdown.text.trim_end()))
+}
+
+// The text of the first '<pre>' block in the description, which is the example input
+// on every puzzle page so far.
+pub(crate) fn first_example(html: &str) -> Option<String> {
+    let tokens = tokenize(html);
+    let article = *articles(&tokens).first()?;
+    let start = article
+        .iter()
+        .position(|token| matches!(token, Token::Open("pre", _)))?;
+    let example = article[start..]
+        .iter()
+        .take_while(|token| **token != Token::Close("pre"))
+        .filter_map(|token| match token {
+            Token::Text(text) => Some(decode_entities(text)),
+            _ => None,
+        })
+        .collect();
+    Some(example)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Trimmed from a saved copy of https://adventofcode.com/2022/day/5 with part 2 unlocked.
+    const PAGE: &str = r#"<!DOCTYPE html>
+<html lang="en-us"><head><title>Day 5 - Advent of Code 2022</title></head><body>
+<main>
+<article class="day-desc"><h2>--- Day 5: Supply Stacks ---</h2><p>The expedition can depart as soon as the final supplies have been unloaded from the ships.</p>
+<p>For example:</p>
+<pre><code>    [D]
+[N] [C]
+[Z] [M] [P]
+ 1   2   3
+
+move 1 from 2 to 1
+</code></pre>
+<ul>
+<li>In the first step of the <span title="Or a crane.">rearrangement</span>, one crate is moved.</li>
+<li>Then, <em>three</em> crates &amp; more are moved.</li>
+</ul>
+<p>The top crates are <code><em>CMZ</em></code>. <a href="/2022/day/5/input">Get your puzzle input</a>.</p>
+</article>
+<p>Your puzzle answer was <code>XYZ</code>.</p>
+<article class="day-desc"><h2 id="part2">--- Part Two ---</h2><p>Some mud was covering the writing on the side of the crane.</p>
+</article>
+</main></body></html>"#;
+
+    #[test]
+    fn test_to_markdown() {
+        let expected = "\
+## Day 5: Supply Stacks
+
+The expedition can depart as soon as the final supplies have been unloaded from the ships.
+
+For example:
+
+```
+    [D]
+[N] [C]
+[Z] [M] [P]
+ 1   2   3
+
+move 1 from 2 to 1
+```
+
+- In the first step of the rearrangement, one crate is moved.
+- Then, *three* crates & more are moved.
+
+The top crates are `CMZ`. [Get your puzzle input](https://adventofcode.com/2022/day/5/input).
+
+## Part Two
+
+Some mud was covering the writing on the side of the crane.
+";
+        assert_eq!(to_markdown(PAGE).unwrap(), expected);
+        assert!(matches!(
+            to_markdown("<p>Not logged in</p>"),
+            Err(DescribeError::MissingArticle)
+        ));
+    }
+
+    #[test]
+    fn test_first_example() {
+        let example = first_example(PAGE).unwrap();
+        assert!(example.starts_with("    [D]\n[N] [C]\n"));
+        assert!(example.ends_with("move 1 from 2 to 1\n"));
+    }
+}
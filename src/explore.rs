@@ -0,0 +1,84 @@
+use std::error;
+use std::io::{self, BufRead, Write};
+
+// A day's parsed puzzle state that can be inspected and changed one command at a time.
+// Each day keeps its own undo history, since only it knows which commands change state.
+pub(crate) trait Explorer {
+    fn execute(&mut self, command: &str) -> Result<String, Box<dyn error::Error>>;
+
+    fn help(&self) -> &'static str;
+}
+
+pub(crate) fn repl(
+    number: u8,
+    explorer: &mut dyn Explorer,
+    input: impl BufRead,
+    mut output: impl Write,
+) -> io::Result<()> {
+    writeln!(
+        output,
+        "Exploring day {number:02}. Type 'help' for commands, 'quit' to exit."
+    )?;
+    write!(output, "day{number:02}> ")?;
+    output.flush()?;
+
+    for line in input.lines() {
+        let command = line?;
+        match command.trim() {
+            "" => {}
+            "quit" | "exit" => break,
+            "help" => writeln!(output, "{}", explorer.help())?,
+            command => match explorer.execute(command) {
+                Ok(result) if result.is_empty() => {}
+                Ok(result) => writeln!(output, "{result}")?,
+                Err(e) => writeln!(output, "error: {e}")?,
+            },
+        }
+        write!(output, "day{number:02}> ")?;
+        output.flush()?;
+    }
+    writeln!(output)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Echoes commands back, failing on "fail".
+    struct Echo;
+
+    impl Explorer for Echo {
+        fn execute(&mut self, command: &str) -> Result<String, Box<dyn error::Error>> {
+            match command {
+                "fail" => Err("it failed".into()),
+                "quiet" => Ok(String::new()),
+                _ => Ok(format!("echo {command}")),
+            }
+        }
+
+        fn help(&self) -> &'static str {
+            "commands"
+        }
+    }
+
+    #[test]
+    fn test_repl() {
+        let input = "help\n\n  ping  \nquiet\nfail\nquit\nping\n";
+        let mut output = Vec::new();
+        repl(3, &mut Echo, input.as_bytes(), &mut output).unwrap();
+        let output = String::from_utf8(output).unwrap();
+        let expected = "Exploring day 03. Type 'help' for commands, 'quit' to exit.\n\
+                        day03> commands\n\
+                        day03> day03> echo ping\n\
+                        day03> day03> error: it failed\n\
+                        day03> \n";
+        assert_eq!(output, expected);
+
+        // The end of the input ends the session like 'quit'.
+        let mut output = Vec::new();
+        repl(3, &mut Echo, "ping".as_bytes(), &mut output).unwrap();
+        assert!(String::from_utf8(output)
+            .unwrap()
+            .ends_with("echo ping\nday03> \n"));
+    }
+}
@@ -0,0 +1,44 @@
+use std::env;
+use std::error;
+use std::fs;
+
+const BASE_URL: &str = "https://adventofcode.com";
+pub(crate) const YEAR: u16 = 2022;
+
+// Identifies the tool to the Advent of Code servers, as their automation guidelines ask.
+const USER_AGENT: &str = "github.com/TimothySimons/adventofcode";
+
+// The value of the 'session' cookie of a logged in browser, which personal pages (inputs, and
+// puzzle pages with part 2 unlocked) need. Read from 'AOC_SESSION', or else from a '.session'
+// file in the working directory, which is kept out of git.
+fn session_token() -> Result<String, Box<dyn error::Error>> {
+    if let Ok(token) = env::var("AOC_SESSION") {
+        return Ok(token.trim().to_string());
+    }
+    match fs::read_to_string(".session") {
+        Ok(token) => Ok(token.trim().to_string()),
+        Err(_) => Err("No session token: set AOC_SESSION or write it to a '.session' file.".into()),
+    }
+}
+
+pub(crate) fn absolute_url(path: &str) -> String {
+    if path.starts_with('/') {
+        format!("{BASE_URL}{path}")
+    } else {
+        path.to_string()
+    }
+}
+
+// Downloads a page of this year's event, such as "day/5" or "day/5/input".
+#[tracing::instrument]
+pub(crate) fn page(path: &str) -> Result<String, Box<dyn error::Error>> {
+    let url = absolute_url(&format!("/{YEAR}/{path}"));
+    let token = session_token()?;
+    let body = ureq::get(&url)
+        .header("Cookie", &format!("session={token}"))
+        .header("User-Agent", USER_AGENT)
+        .call()?
+        .body_mut()
+        .read_to_string()?;
+    Ok(body)
+}
@@ -0,0 +1,210 @@
+use std::fmt;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub(crate) struct Point {
+    pub(crate) x: usize,
+    pub(crate) y: usize,
+}
+
+impl Point {
+    pub(crate) fn new(x: usize, y: usize) -> Self {
+        Self { x, y }
+    }
+
+    // 'None' when the step would leave the first quadrant; the grid checks the upper bounds.
+    fn offset(self, (dx, dy): (isize, isize)) -> Option<Point> {
+        Some(Point {
+            x: self.x.checked_add_signed(dx)?,
+            y: self.y.checked_add_signed(dy)?,
+        })
+    }
+}
+
+const ORTHOGONAL: [(isize, isize); 4] = [(0, -1), (1, 0), (0, 1), (-1, 0)];
+const DIAGONAL: [(isize, isize); 4] = [(1, -1), (1, 1), (-1, 1), (-1, -1)];
+
+// A rectangular grid stored row by row; 'y' grows downwards, as in the puzzle drawings.
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) struct Grid<T> {
+    width: usize,
+    height: usize,
+    cells: Vec<T>,
+}
+
+impl Grid<char> {
+    // Parses character art. Lines are often stored without their trailing spaces,
+    // so shorter lines are padded with spaces up to the longest one.
+    pub(crate) fn parse(art: &str) -> Self {
+        let lines: Vec<&str> = art.lines().collect();
+        let width = lines
+            .iter()
+            .map(|line| line.chars().count())
+            .max()
+            .unwrap_or(0);
+        let mut cells = Vec::with_capacity(width * lines.len());
+        for line in &lines {
+            let start = cells.len();
+            cells.extend(line.chars());
+            cells.resize(start + width, ' ');
+        }
+        Self {
+            width,
+            height: lines.len(),
+            cells,
+        }
+    }
+}
+
+impl<T> Grid<T> {
+    pub(crate) fn width(&self) -> usize {
+        self.width
+    }
+
+    pub(crate) fn height(&self) -> usize {
+        self.height
+    }
+
+    pub(crate) fn contains(&self, point: Point) -> bool {
+        point.x < self.width && point.y < self.height
+    }
+
+    pub(crate) fn get(&self, point: Point) -> Option<&T> {
+        self.contains(point)
+            .then(|| &self.cells[point.y * self.width + point.x])
+    }
+
+    pub(crate) fn get_mut(&mut self, point: Point) -> Option<&mut T> {
+        if self.contains(point) {
+            Some(&mut self.cells[point.y * self.width + point.x])
+        } else {
+            None
+        }
+    }
+
+    // 'None' when 'y' is outside the grid, like 'get'.
+    pub(crate) fn row(&self, y: usize) -> Option<impl Iterator<Item = &T>> {
+        (y < self.height).then(|| self.cells[y * self.width..(y + 1) * self.width].iter())
+    }
+
+    // 'None' when 'x' is outside the grid, like 'get'.
+    pub(crate) fn column(&self, x: usize) -> Option<impl Iterator<Item = &T>> {
+        (x < self.width).then(|| self.cells[x..].iter().step_by(self.width))
+    }
+}
+
+// Neighbourhoods and views for grid puzzles that have not been solved yet; so far only the tests
+// (here and in 'search') use them.
+#[allow(dead_code)]
+impl<T> Grid<T> {
+    fn neighbours<'a>(
+        &'a self,
+        point: Point,
+        offsets: impl Iterator<Item = &'a (isize, isize)> + 'a,
+    ) -> impl Iterator<Item = Point> + 'a {
+        offsets
+            .filter_map(move |offset| point.offset(*offset))
+            .filter(|neighbour| self.contains(*neighbour))
+    }
+
+    // Up, right, down and left of 'point', skipping any that fall outside the grid.
+    pub(crate) fn neighbours4(&self, point: Point) -> impl Iterator<Item = Point> + '_ {
+        self.neighbours(point, ORTHOGONAL.iter())
+    }
+
+    // The four orthogonal neighbours followed by the four diagonal ones.
+    pub(crate) fn neighbours8(&self, point: Point) -> impl Iterator<Item = Point> + '_ {
+        self.neighbours(point, ORTHOGONAL.iter().chain(DIAGONAL.iter()))
+    }
+
+    // Walks down and to the right from 'start' until the edge of the grid.
+    pub(crate) fn diagonal(&self, start: Point) -> impl Iterator<Item = &T> {
+        (0..)
+            .map_while(move |i| start.offset((i, i)))
+            .map_while(|point| self.get(point))
+    }
+
+    // Walks down and to the left from 'start' until the edge of the grid.
+    pub(crate) fn anti_diagonal(&self, start: Point) -> impl Iterator<Item = &T> {
+        (0..)
+            .map_while(move |i| start.offset((-i, i)))
+            .map_while(|point| self.get(point))
+    }
+}
+
+impl<T: Clone> Grid<T> {
+    pub(crate) fn new(width: usize, height: usize, fill: T) -> Self {
+        Self {
+            width,
+            height,
+            cells: vec![fill; width * height],
+        }
+    }
+
+    // Rows become columns: the cell at (x, y) moves to (y, x). Like the views above, only the tests
+    // use it so far.
+    #[allow(dead_code)]
+    pub(crate) fn transpose(&self) -> Self {
+        let mut cells = Vec::with_capacity(self.cells.len());
+        for x in 0..self.width {
+            cells.extend(self.column(x).into_iter().flatten().cloned());
+        }
+        Self {
+            width: self.height,
+            height: self.width,
+            cells,
+        }
+    }
+}
+
+// Prints the grid as character art again, one row per line.
+impl<T: fmt::Display> fmt::Display for Grid<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for y in 0..self.height {
+            if y > 0 {
+                writeln!(f)?;
+            }
+            for cell in self.row(y).into_iter().flatten() {
+                write!(f, "{cell}")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_pads_ragged_lines() {
+        let grid = Grid::parse("ab\nc\ndef");
+        assert_eq!((grid.width(), grid.height()), (3, 3));
+        assert_eq!(grid.get(Point::new(2, 1)), Some(&' '));
+        assert_eq!(grid.get(Point::new(3, 0)), None);
+        assert_eq!(grid.to_string(), "ab \nc  \ndef");
+    }
+
+    #[test]
+    fn test_neighbours() {
+        let grid = Grid::new(3, 3, 0);
+        assert_eq!(grid.neighbours4(Point::new(0, 0)).count(), 2);
+        assert_eq!(grid.neighbours8(Point::new(0, 0)).count(), 3);
+        assert_eq!(grid.neighbours8(Point::new(1, 1)).count(), 8);
+    }
+
+    #[test]
+    fn test_views() {
+        let grid = Grid::parse("abc\ndef");
+        assert_eq!(grid.row(1).unwrap().collect::<String>(), "def");
+        assert_eq!(grid.column(1).unwrap().collect::<String>(), "be");
+        assert!(grid.row(2).is_none());
+        assert!(grid.column(3).is_none());
+        assert!(Grid::parse("").column(0).is_none());
+        assert_eq!(grid.diagonal(Point::new(0, 0)).collect::<String>(), "ae");
+        assert_eq!(
+            grid.anti_diagonal(Point::new(2, 0)).collect::<String>(),
+            "ce"
+        );
+        assert_eq!(grid.transpose().to_string(), "ad\nbe\ncf");
+    }
+}
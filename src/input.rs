@@ -0,0 +1,26 @@
+use std::fs::File;
+use std::io::{self, BufRead, BufReader};
+
+use memmap2::Mmap;
+
+// Streams a puzzle input one line at a time, so memory use is bounded by the longest line
+// rather than the size of the file. Line endings are stripped, and a trailing newline
+// at the end of the file does not produce an extra empty line.
+pub(crate) fn lines(file_path: &str) -> io::Result<io::Lines<BufReader<File>>> {
+    Ok(reader(file_path)?.lines())
+}
+
+// For solvers that read lines into a buffer of their own with 'read_line', to avoid
+// allocating a new 'String' per line like 'lines' does.
+pub(crate) fn reader(file_path: &str) -> io::Result<BufReader<File>> {
+    Ok(BufReader::new(File::open(file_path)?))
+}
+
+// Maps a puzzle input into memory. The OS pages the file in (and out) on demand,
+// so a multi-gigabyte input can be scanned as a '&[u8]' without reading it into the heap.
+pub(crate) fn map(file_path: &str) -> io::Result<Mmap> {
+    let file = File::open(file_path)?;
+    // 'unsafe' because another process could truncate or modify the file while it is mapped,
+    // which is undefined behaviour for the '&[u8]' handed out. Puzzle inputs are not modified while solving.
+    unsafe { Mmap::map(&file) }
+}
@@ -0,0 +1,237 @@
+use std::cmp::{Ordering, Reverse};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::error;
+use std::fs;
+use std::path::Path;
+
+use crate::answers;
+use crate::runner;
+
+// The JSON export of a private leaderboard ("[API]" on the leaderboard page), saved to a file.
+// Only the fields used here are read; day and part keys are numbers written as strings.
+#[derive(serde::Deserialize)]
+pub(crate) struct Leaderboard {
+    owner_id: u64,
+    members: HashMap<String, Member>,
+}
+
+#[derive(serde::Deserialize)]
+struct Member {
+    id: u64,
+    name: Option<String>,
+    local_score: u64,
+    stars: u32,
+    #[serde(default)]
+    last_star_ts: i64,
+    completion_day_level: BTreeMap<u8, BTreeMap<u8, Star>>,
+}
+
+#[derive(serde::Deserialize)]
+struct Star {
+    get_star_ts: i64,
+}
+
+impl Member {
+    // Members without a public name show up like they do on the website.
+    fn display_name(&self) -> String {
+        match &self.name {
+            Some(name) => name.clone(),
+            None => format!("(anonymous user #{})", self.id),
+        }
+    }
+
+    fn stars_on(&self, day: u8) -> usize {
+        self.completion_day_level.get(&day).map_or(0, BTreeMap::len)
+    }
+
+    // Seconds between getting the first and second star of a day, for days with both.
+    fn part_deltas(&self) -> Vec<(u8, i64)> {
+        self.completion_day_level
+            .iter()
+            .filter_map(|(day, parts)| {
+                let part1 = parts.get(&1)?.get_star_ts;
+                let part2 = parts.get(&2)?.get_star_ts;
+                Some((*day, part2 - part1))
+            })
+            .collect()
+    }
+}
+
+impl Leaderboard {
+    pub(crate) fn load(path: &Path) -> Result<Self, Box<dyn error::Error>> {
+        Ok(serde_json::from_str(&fs::read_to_string(path)?)?)
+    }
+
+    // Highest local score first, ties going to whoever got their last star earliest, as on the website.
+    fn ranking(&self) -> Vec<&Member> {
+        let mut members: Vec<&Member> = self.members.values().collect();
+        members.sort_by_key(|member| (Reverse(member.local_score), member.last_star_ts, member.id));
+        members
+    }
+
+    fn last_day(&self) -> u8 {
+        self.members
+            .values()
+            .filter_map(|member| member.completion_day_level.keys().max().copied())
+            .max()
+            .unwrap_or(0)
+    }
+
+    // 'member' is matched against ids first, then names; the board's owner when not given.
+    fn find_member(&self, member: Option<&str>) -> Result<&Member, Box<dyn error::Error>> {
+        let found = match member {
+            None => self
+                .members
+                .values()
+                .find(|candidate| candidate.id == self.owner_id),
+            Some(wanted) => self
+                .members
+                .values()
+                .find(|candidate| candidate.id.to_string() == wanted)
+                .or_else(|| {
+                    self.members
+                        .values()
+                        .find(|candidate| candidate.name.as_deref() == Some(wanted))
+                }),
+        };
+        found.ok_or_else(|| {
+            format!(
+                "No member {} on the leaderboard.",
+                member.unwrap_or("(owner)")
+            )
+            .into()
+        })
+    }
+}
+
+fn format_delta(seconds: i64) -> String {
+    format!(
+        "{}:{:02}:{:02}",
+        seconds / 3600,
+        seconds / 60 % 60,
+        seconds % 60
+    )
+}
+
+pub(crate) fn print_ranking(board: &Leaderboard) {
+    let last_day = board.last_day();
+    let days: String = (1..=last_day)
+        .map(|day| format!("{:<3}", day % 10))
+        .collect();
+    println!(
+        "{:<5} {:<24} {:>6} {:>6}  {}",
+        "Rank",
+        "Member",
+        "Score",
+        "Stars",
+        days.trim_end()
+    );
+    for (rank, member) in board.ranking().iter().enumerate() {
+        let stars: String = (1..=last_day)
+            .map(|day| match member.stars_on(day) {
+                0 => "   ",
+                1 => "*  ",
+                _ => "** ",
+            })
+            .collect();
+        println!(
+            "{:<5} {:<24} {:>6} {:>6}  {}",
+            rank + 1,
+            member.display_name(),
+            member.local_score,
+            member.stars,
+            stars.trim_end()
+        );
+    }
+}
+
+pub(crate) fn print_deltas(board: &Leaderboard) {
+    println!("{:<24} {:<4} {:>12}", "Member", "Day", "Part 1 -> 2");
+    for member in board.ranking() {
+        for (day, delta) in member.part_deltas() {
+            println!(
+                "{:<24} {:<4} {:>12}",
+                member.display_name(),
+                format!("{day:02}"),
+                format_delta(delta)
+            );
+        }
+    }
+}
+
+// Compares the stars 'member' has on the board with the answers saved locally in
+// resources/dayNN.answers, for every registered day and every day with a star.
+pub(crate) fn print_comparison(
+    board: &Leaderboard,
+    member: Option<&str>,
+) -> Result<(), Box<dyn error::Error>> {
+    let member = board.find_member(member)?;
+    let days: BTreeSet<u8> = runner::DAYS
+        .iter()
+        .map(|day| day.number)
+        .chain(member.completion_day_level.keys().copied())
+        .collect();
+
+    println!("Local answers compared with {}:", member.display_name());
+    println!("{:<4} {:>6} {:>6}  Note", "Day", "Local", "Board");
+    for day in days {
+        let known = answers::load(Path::new(&runner::input_path(day)))?;
+        let local = known.map_or(0, |known| {
+            (1..=2).filter(|part| known.part(*part).is_some()).count()
+        });
+        let board_stars = member.stars_on(day);
+        let note = match local.cmp(&board_stars) {
+            Ordering::Equal => "",
+            Ordering::Less => "answer missing locally",
+            Ordering::Greater => "star missing on the board",
+        };
+        let row = format!(
+            "{:<4} {:>6} {:>6}  {note}",
+            format!("{day:02}"),
+            local,
+            board_stars
+        );
+        println!("{}", row.trim_end());
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BOARD: &str = r#"{
+        "owner_id": 1,
+        "event": "2022",
+        "members": {
+            "1": {"id": 1, "name": "alice", "local_score": 10, "stars": 3, "last_star_ts": 500, "global_score": 0,
+                  "completion_day_level": {"1": {"1": {"get_star_ts": 100, "star_index": 0},
+                                                 "2": {"get_star_ts": 3825, "star_index": 1}},
+                                           "2": {"1": {"get_star_ts": 500, "star_index": 2}}}},
+            "2": {"id": 2, "name": null, "local_score": 10, "stars": 2, "last_star_ts": 400, "global_score": 0,
+                  "completion_day_level": {"1": {"1": {"get_star_ts": 300, "star_index": 3},
+                                                 "2": {"get_star_ts": 400, "star_index": 4}}}}
+        }
+    }"#;
+
+    #[test]
+    fn test_leaderboard() {
+        let board: Leaderboard = serde_json::from_str(BOARD).unwrap();
+        let ranking: Vec<String> = board
+            .ranking()
+            .iter()
+            .map(|member| member.display_name())
+            .collect();
+        assert_eq!(ranking, ["(anonymous user #2)", "alice"]);
+
+        let alice = board.find_member(Some("alice")).unwrap();
+        assert_eq!(alice.id, board.find_member(None).unwrap().id);
+        assert_eq!(
+            (alice.stars_on(1), alice.stars_on(2), alice.stars_on(3)),
+            (2, 1, 0)
+        );
+        assert_eq!(alice.part_deltas(), [(1, 3725)]);
+        assert_eq!(format_delta(3725), "1:02:05");
+        assert!(board.find_member(Some("bob")).is_err());
+    }
+}
@@ -0,0 +1,53 @@
+use std::error;
+use std::path::Path;
+
+use tracing::level_filters::LevelFilter;
+use tracing_chrome::{ChromeLayerBuilder, FlushGuard};
+use tracing_subscriber::prelude::*;
+use tracing_subscriber::EnvFilter;
+
+// Filter precedence: an explicit '--log' filter, then '-v' flags, then the 'RUST_LOG' variable.
+// Without any of these only warnings and errors are printed.
+fn env_filter(verbose: u8, log: Option<&str>) -> Result<EnvFilter, Box<dyn error::Error>> {
+    if let Some(directives) = log {
+        return Ok(EnvFilter::try_new(directives)?);
+    }
+    let level = match verbose {
+        0 => {
+            return Ok(EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("warn")))
+        }
+        1 => LevelFilter::INFO,
+        2 => LevelFilter::DEBUG,
+        _ => LevelFilter::TRACE,
+    };
+    Ok(EnvFilter::default().add_directive(level.into()))
+}
+
+// The Chrome layer gets its own filter so that a trace file always contains the parse/solve spans,
+// even when nothing is printed to the terminal.
+pub(crate) fn init(
+    verbose: u8,
+    log: Option<&str>,
+    trace_file: Option<&Path>,
+) -> Result<Option<FlushGuard>, Box<dyn error::Error>> {
+    let fmt_layer = tracing_subscriber::fmt::layer()
+        .with_writer(std::io::stderr)
+        .with_filter(env_filter(verbose, log)?);
+
+    let (chrome_layer, guard) = match trace_file {
+        Some(path) => {
+            let (layer, guard) = ChromeLayerBuilder::new()
+                .file(path)
+                .include_args(true)
+                .build();
+            (Some(layer.with_filter(LevelFilter::INFO)), Some(guard))
+        }
+        None => (None, None),
+    };
+
+    tracing_subscriber::registry()
+        .with(fmt_layer)
+        .with(chrome_layer)
+        .try_init()?;
+    Ok(guard)
+}
@@ -1,30 +1,473 @@
-// mod day01;
-// mod day02;
-// mod day03;
-// mod day04;
-// mod day05;
-// mod day06;
+mod answers;
+mod arith;
+mod bench;
+mod cache;
+mod day01;
+mod day02;
+mod day03;
+mod day04;
+mod day05;
+mod day06;
 mod day07;
+mod describe;
+mod explore;
+mod fetch;
+mod grid;
+mod input;
+mod leaderboard;
+mod logging;
+mod memory;
+mod parse;
+mod report;
+mod runner;
+mod search;
+mod viz;
+
+use std::io;
+use std::path::{Path, PathBuf};
+
+use cache::Cache;
+use clap::builder::{PossibleValuesParser, TypedValueParser};
+use clap::{CommandFactory, Parser, Subcommand};
+
+#[cfg(feature = "alloc-stats")]
+#[global_allocator]
+static GLOBAL: memory::CountingAllocator = memory::CountingAllocator;
+
+/// Advent of Code 2022 solutions.
+#[derive(Parser)]
+#[command(version, about)]
+struct Cli {
+    /// Increase log verbosity (-v info, -vv debug, -vvv trace).
+    #[arg(short, long, action = clap::ArgAction::Count, global = true)]
+    verbose: u8,
+
+    /// Log filter in `RUST_LOG` syntax (e.g. `adventofcode::day05=debug`); overrides -v.
+    #[arg(long, global = true)]
+    log: Option<String>,
+
+    /// Write a Chrome trace (viewable in chrome://tracing or Perfetto) to this file.
+    #[arg(long, global = true)]
+    trace_file: Option<PathBuf>,
+
+    /// Report arithmetic overflow in any day as an error instead of wrapping (implies --no-cache).
+    #[arg(long, global = true)]
+    checked: bool,
+
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Solve a single day, or every registered day.
+    Run {
+        /// Day number to solve.
+        #[arg(long, required_unless_present = "all", value_parser = day_number(|_| true))]
+        day: Option<u8>,
+
+        /// Solve every registered day.
+        #[arg(long, conflicts_with = "day")]
+        all: bool,
+
+        /// Run the day on every input in this directory, checking each against a sibling
+        /// '<name>.answers' file (part 1 answer on the first line, part 2 on the second).
+        #[arg(long, requires = "day")]
+        inputs: Option<PathBuf>,
+
+        /// Print the results as JSON instead of a table.
+        #[arg(long)]
+        json: bool,
+
+        /// Always recompute, ignoring and not updating the answer cache.
+        #[arg(long)]
+        no_cache: bool,
+
+        /// Day 1 only: list the K elves carrying the most calories instead of solving.
+        #[arg(long, value_name = "K", requires = "day")]
+        top: Option<usize>,
+
+        /// Day 1 only: the fewest item transfers so no elf carries more than N calories.
+        #[arg(long, value_name = "N", requires = "day", conflicts_with_all = ["top", "spread"])]
+        cap: Option<u64>,
+
+        /// Day 1 only: item transfers so the heaviest and lightest elf are less than N calories apart.
+        #[arg(long, value_name = "N", requires = "day", conflicts_with = "top")]
+        spread: Option<u64>,
+
+        /// Day 2 only: play the strategy guide as the cyclic game described in this JSON file.
+        #[arg(long, value_name = "FILE", requires = "day")]
+        game: Option<PathBuf>,
+
+        /// Day 2 only: score the guide under every reading of X/Y/Z instead of solving.
+        #[arg(long, requires = "day", conflicts_with = "game")]
+        decode: bool,
+
+        /// With --decode, mark the readings that give this total score.
+        #[arg(long, value_name = "SCORE", requires = "decode")]
+        target: Option<u64>,
+
+        /// Day 2 only: the best responses to the opponent's column, round by round.
+        #[arg(long, requires = "day", conflicts_with_all = ["game", "decode"])]
+        counter: bool,
+
+        /// With --counter, lose exactly N rounds and win or draw the rest.
+        #[arg(long, value_name = "N", requires = "counter")]
+        lose: Option<usize>,
+
+        /// Day 2 only: a round-robin between these strategy guides, one per player.
+        #[arg(long, value_name = "FILE", num_args = 2.., requires = "day", conflicts_with_all = ["game", "decode", "counter"])]
+        tournament: Vec<PathBuf>,
+
+        /// With --tournament, also track every player's Elo rating across the rounds.
+        #[arg(long, requires = "tournament")]
+        elo: bool,
+
+        /// Day 2 only: print every round of both interpretations with running totals.
+        #[arg(long, value_name = "FORMAT", requires = "day", conflicts_with_all = ["game", "decode", "counter", "tournament"])]
+        ledger: Option<LedgerFormat>,
+
+        /// Animate the day's state in the terminal before solving it.
+        #[arg(long, requires = "day")]
+        visualize: bool,
+
+        /// Animation speed in frames per second.
+        #[arg(long, default_value_t = 10.0, value_parser = viz::parse_speed, requires = "visualize")]
+        speed: f64,
+
+        /// Save the animation as an asciinema cast file instead of playing it.
+        #[arg(long, requires = "visualize")]
+        cast: Option<PathBuf>,
+    },
+    /// Interactively inspect and modify a day's parsed puzzle state.
+    Explore {
+        /// Day number to explore.
+        #[arg(long, value_parser = day_number(|day| day.explore.is_some()))]
+        day: u8,
+
+        /// Input file to load instead of resources/dayNN.txt.
+        #[arg(long)]
+        input: Option<PathBuf>,
+    },
+    /// Solve every registered day and write a self-contained HTML report.
+    Report {
+        /// Where to write the HTML page.
+        #[arg(long)]
+        html: PathBuf,
+    },
+    /// Save a day's puzzle description as resources/dayNN.md and its first example as
+    /// resources/dayNN_example.txt.
+    Describe {
+        /// Day number to describe; any day of the event, solved or not.
+        #[arg(long, value_parser = clap::value_parser!(u8).range(1..=25))]
+        day: u8,
+
+        /// Convert this saved puzzle page instead of downloading it.
+        #[arg(long)]
+        html: Option<PathBuf>,
+    },
+    /// Show stars, part 1 to 2 times and rankings from a saved private leaderboard JSON file.
+    Leaderboard {
+        /// The leaderboard's JSON export.
+        #[arg(long)]
+        file: PathBuf,
+
+        /// Member (id or name) whose stars are compared with the local answers; defaults to the owner.
+        #[arg(long)]
+        member: Option<String>,
+    },
+    /// Race a day's implementations on a generated input, e.g. multi-GB calorie lists for day 1.
+    Bench {
+        /// Day number to benchmark.
+        #[arg(long, value_parser = day_number(|day| day.bench.is_some()))]
+        day: u8,
+
+        /// Size of the generated input, in the day's unit (MB for day 1).
+        #[arg(long)]
+        size: Option<u64>,
+
+        /// Keep the generated input at this path instead of deleting it afterwards.
+        #[arg(long)]
+        keep: Option<PathBuf>,
+    },
+    /// Print a shell completion script to stdout.
+    Completions {
+        /// Shell to generate the script for.
+        shell: clap_complete::Shell,
+    },
+    /// Print a roff man page to stdout.
+    Man,
+}
+
+// The '--ledger' formats. Kept here with the rest of the CLI and matched to 'day02::LedgerFormat',
+// so the solver does not depend on clap.
+#[derive(Clone, Copy, clap::ValueEnum)]
+enum LedgerFormat {
+    Csv,
+    Json,
+}
+
+// Day arguments only accept (and shell completion only offers) the days in the registry
+// that pass 'filter', rather than a hardcoded 1-25 range.
+fn day_number(filter: fn(&runner::Day) -> bool) -> impl TypedValueParser<Value = u8> {
+    let numbers = runner::DAYS
+        .iter()
+        .filter(move |day| filter(day))
+        .map(|day| day.number.to_string());
+    PossibleValuesParser::new(numbers)
+        .map(|number| number.parse().expect("registered day numbers fit in a u8"))
+}
 
 fn main() -> Result<(), Box<dyn std::error::Error>> {
-    // println!("Day 01 part 1: {}", day01::part1("resources/day01.txt"));
-    // println!("Day 01 part 2: {}", day01::part2("resources/day01.txt"));
+    let cli = Cli::parse();
+    // The guard flushes the Chrome trace when it is dropped, so it must live until the end of 'main'.
+    let _guard = logging::init(cli.verbose, cli.log.as_deref(), cli.trace_file.as_deref())?;
 
-    // println!("Day 02 part 1: {}", day02::part1("resources/day02.txt"));
-    // println!("Day 02 part 2: {}", day02::part2("resources/day02.txt"));
+    arith::set_checked(cli.checked);
 
-    // println!("day 03 part 1: {}", day03::part1("resources/day03.txt"));
-    // println!("day 03 part 2: {}", day03::part2("resources/day03.txt"));
+    if let Command::Run {
+        day: Some(day),
+        visualize: true,
+        speed,
+        cast,
+        ..
+    } = &cli.command
+    {
+        visualize(*day, *speed, cast.as_deref())?;
+    }
 
-    // println!("day 04 part 1: {}", day04::part1("resources/day04.txt"));
-    // println!("day 04 part 2: {}", day04::part2("resources/day04.txt"));
+    match cli.command {
+        Command::Run {
+            day: Some(1),
+            top: Some(k),
+            ..
+        } => {
+            println!("{}", day01::top(&runner::input_path(1), k)?);
+            Ok(())
+        }
+        Command::Run {
+            day: Some(1),
+            cap,
+            spread,
+            ..
+        } if cap.is_some() || spread.is_some() => {
+            let goal = match cap {
+                Some(cap) => day01::Goal::Cap(cap),
+                None => day01::Goal::Spread(spread.unwrap_or_default()),
+            };
+            println!("{}", day01::rebalance(&runner::input_path(1), goal)?);
+            Ok(())
+        }
+        Command::Run {
+            day: Some(2),
+            game: Some(config),
+            ..
+        } => {
+            println!("{}", day02::play_config(&runner::input_path(2), &config)?);
+            Ok(())
+        }
+        Command::Run {
+            day: Some(2),
+            decode: true,
+            target,
+            ..
+        } => {
+            println!("{}", day02::decode(&runner::input_path(2), target)?);
+            Ok(())
+        }
+        Command::Run {
+            day: Some(2),
+            counter: true,
+            lose,
+            ..
+        } => {
+            println!("{}", day02::counter(&runner::input_path(2), lose)?);
+            Ok(())
+        }
+        Command::Run {
+            day: Some(2),
+            tournament,
+            elo,
+            ..
+        } if !tournament.is_empty() => {
+            println!("{}", day02::play_tournament(&tournament, elo)?);
+            Ok(())
+        }
+        Command::Run {
+            day: Some(2),
+            ledger: Some(format),
+            ..
+        } => {
+            let format = match format {
+                LedgerFormat::Csv => day02::LedgerFormat::Csv,
+                LedgerFormat::Json => day02::LedgerFormat::Json,
+            };
+            println!("{}", day02::export_ledger(&runner::input_path(2), format)?);
+            Ok(())
+        }
+        Command::Run {
+            game,
+            decode,
+            counter,
+            tournament,
+            ledger,
+            ..
+        } if game.is_some() || decode || counter || !tournament.is_empty() || ledger.is_some() => {
+            Err(
+                "--game, --decode, --counter, --tournament and --ledger only apply to day 2."
+                    .into(),
+            )
+        }
+        Command::Run { top: Some(_), .. }
+        | Command::Run { cap: Some(_), .. }
+        | Command::Run {
+            spread: Some(_), ..
+        } => Err("--top, --cap and --spread only apply to day 1.".into()),
+        Command::Run {
+            day,
+            inputs: Some(directory),
+            no_cache,
+            ..
+        } => run_inputs(day, &directory, no_cache || cli.checked),
+        Command::Run {
+            day,
+            json,
+            no_cache,
+            ..
+        } => run(day, json, no_cache || cli.checked),
+        Command::Explore { day, input } => explore(day, input),
+        Command::Report { html } => {
+            let reports = report::collect();
+            std::fs::write(&html, report::render_html(&reports))?;
+            println!("Wrote {}", html.display());
+            Ok(())
+        }
+        Command::Describe { day, html } => describe(day, html),
+        Command::Leaderboard { file, member } => {
+            let board = leaderboard::Leaderboard::load(&file)?;
+            leaderboard::print_ranking(&board);
+            println!();
+            leaderboard::print_deltas(&board);
+            println!();
+            leaderboard::print_comparison(&board, member.as_deref())
+        }
+        Command::Bench { day, size, keep } => bench(day, size, keep.as_deref()),
+        Command::Completions { shell } => {
+            let mut command = Cli::command();
+            let name = command.get_name().to_string();
+            clap_complete::generate(shell, &mut command, name, &mut io::stdout());
+            Ok(())
+        }
+        Command::Man => {
+            clap_mangen::Man::new(Cli::command()).render(&mut io::stdout())?;
+            Ok(())
+        }
+    }
+}
 
-    // println!("day 05 part 1: {}", day05::part1("resources/day05.txt"));
-    // println!("day 05 part 2: {}", day05::part2("resources/day05.txt"));
+fn run(day: Option<u8>, json: bool, no_cache: bool) -> Result<(), Box<dyn std::error::Error>> {
+    let mut cache = (!no_cache).then(|| Cache::load(Path::new(cache::DEFAULT_PATH)));
+    let mut reports = Vec::new();
+    for day in runner::select(day)? {
+        let path = runner::input_path(day.number);
+        reports.extend(runner::run(day, &path, cache.as_mut())?);
+    }
+    if let Some(cache) = &cache {
+        cache.save()?;
+    }
 
-    // println!("day 06 part 1: {}", day06::part1("resources/day06.txt"));
-    // println!("day 06 part 2: {}", day06::part2("resources/day06.txt"));
+    if json {
+        println!("{}", serde_json::to_string_pretty(&reports)?);
+    } else {
+        runner::print_table(&reports);
+    }
+    Ok(())
+}
+
+fn run_inputs(
+    day: Option<u8>,
+    directory: &Path,
+    no_cache: bool,
+) -> Result<(), Box<dyn std::error::Error>> {
+    // '--inputs' requires '--day', so exactly one day is selected here.
+    let day = runner::select(day)?[0];
+    let mut cache = (!no_cache).then(|| Cache::load(Path::new(cache::DEFAULT_PATH)));
+    let reports = runner::run_inputs(day, directory, cache.as_mut())?;
+    if let Some(cache) = &cache {
+        cache.save()?;
+    }
+
+    runner::print_input_table(&reports);
+    let failed = reports.iter().filter(|report| !report.passed()).count();
+    if failed > 0 {
+        return Err(format!("{failed} of {} input(s) failed.", reports.len()).into());
+    }
+    Ok(())
+}
+
+fn explore(day: u8, input: Option<PathBuf>) -> Result<(), Box<dyn std::error::Error>> {
+    let day = runner::select(Some(day))?[0];
+    let explore = day
+        .explore
+        .ok_or(format!("Day {} has no explorer.", day.number))?;
+    let path = match input {
+        Some(input) => input.to_string_lossy().into_owned(),
+        None => runner::input_path(day.number),
+    };
+    let mut explorer = explore(&path)?;
+    let (stdin, stdout) = (io::stdin().lock(), io::stdout());
+    explore::repl(day.number, explorer.as_mut(), stdin, stdout)?;
+    Ok(())
+}
+
+fn describe(day: u8, html: Option<PathBuf>) -> Result<(), Box<dyn std::error::Error>> {
+    let page = match html {
+        Some(path) => std::fs::read_to_string(path)?,
+        None => fetch::page(&format!("day/{day}"))?,
+    };
+    let markdown_path = format!("resources/day{day:02}.md");
+    std::fs::create_dir_all("resources")?;
+    std::fs::write(&markdown_path, describe::to_markdown(&page)?)?;
+    println!("Wrote {markdown_path}");
+    // Test modules can pull the example in with 'include_str!("../resources/dayNN_example.txt")'.
+    if let Some(example) = describe::first_example(&page) {
+        let example_path = format!("resources/day{day:02}_example.txt");
+        std::fs::write(&example_path, example)?;
+        println!("Wrote {example_path}");
+    }
+    Ok(())
+}
+
+fn visualize(day: u8, speed: f64, cast: Option<&Path>) -> Result<(), Box<dyn std::error::Error>> {
+    let day = runner::find(day).ok_or(format!("Day {day} is not registered."))?;
+    let visualizer = day
+        .visualize
+        .ok_or(format!("Day {:02} has no visualisation.", day.number))?;
+    let path = runner::input_path(day.number);
+    let mut sink: Box<dyn viz::Sink> = match cast {
+        Some(cast) => Box::new(viz::CastWriter::create(cast, speed)?),
+        None => Box::new(viz::Player::new(io::stdout(), speed)),
+    };
+    visualizer(&path, sink.as_mut())?;
+    sink.finish()?;
+    if let Some(cast) = cast {
+        println!("Wrote {}", cast.display());
+    }
+    Ok(())
+}
 
-    println!("day 07 part 1: {}", day07::part1("resources/day07.txt")?);
+fn bench(
+    day: u8,
+    size: Option<u64>,
+    keep: Option<&Path>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let day = runner::find(day).ok_or(format!("Day {day} is not registered."))?;
+    let benchmark = day
+        .bench
+        .as_ref()
+        .ok_or(format!("Day {:02} has no benchmark.", day.number))?;
+    let size = size.unwrap_or(benchmark.default_size);
+    let (bytes, reports) = bench::run(day, benchmark, size, keep)?;
+    bench::print_table(bytes, &reports);
     Ok(())
 }
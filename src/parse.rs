@@ -0,0 +1,190 @@
+// Parsers for the input shapes that keep coming back across puzzles.
+// Each parser handles one line and returns a 'ParseError' with the column where parsing stopped;
+// callers attach the line number with 'at_line', since only they know it.
+use nom::branch::alt;
+use nom::bytes::complete::{tag, take_while1};
+use nom::character::complete::{char, space1, u32, u64};
+use nom::combinator::{all_consuming, map, value};
+use nom::sequence::{preceded, separated_pair, tuple};
+use nom::IResult;
+
+#[derive(Debug, PartialEq, thiserror::Error)]
+#[error("{}expected {expected} at column {column}: {text:?}", match .line { Some(line) => format!("line {line}: "), None => String::new() })]
+pub(crate) struct ParseError {
+    line: Option<usize>,
+    column: usize,
+    expected: &'static str,
+    text: String,
+}
+
+impl ParseError {
+    pub(crate) fn at_line(self, line: usize) -> Self {
+        Self {
+            line: Some(line),
+            ..self
+        }
+    }
+}
+
+// Runs a nom parser over a whole line and converts a failure into a 'ParseError'.
+fn finish<'a, T>(
+    line: &'a str,
+    expected: &'static str,
+    mut parser: impl FnMut(&'a str) -> IResult<&'a str, T>,
+) -> Result<T, ParseError> {
+    match all_consuming(&mut parser)(line) {
+        Ok((_, value)) => Ok(value),
+        Err(nom::Err::Error(e) | nom::Err::Failure(e)) => Err(ParseError {
+            line: None,
+            column: line.len() - e.input.len() + 1,
+            expected,
+            text: String::from(line),
+        }),
+        Err(nom::Err::Incomplete(_)) => Err(ParseError {
+            line: None,
+            column: line.len() + 1,
+            expected,
+            text: String::from(line),
+        }),
+    }
+}
+
+fn token(input: &str) -> IResult<&str, &str> {
+    take_while1(|c: char| !c.is_whitespace())(input)
+}
+
+// One line of a blank-line-separated list of integer groups: 'None' for the blank separator.
+pub(crate) fn group_item(line: &str) -> Result<Option<u64>, ParseError> {
+    if line.is_empty() {
+        return Ok(None);
+    }
+    finish(line, "an integer or a blank line", u64).map(Some)
+}
+
+// Two whitespace-separated tokens, e.g. "A Y".
+pub(crate) fn token_pair(line: &str) -> Result<(&str, &str), ParseError> {
+    finish(line, "two space-separated tokens", |input| {
+        separated_pair(token, space1, token)(input)
+    })
+}
+
+// A strategy guide round, exactly "<A-C> <X-Z>", checked byte by byte without allocating
+// (except for the error). Returns the two symbols.
+pub(crate) fn guide_round(line: &[u8]) -> Result<(char, char), ParseError> {
+    let fail = |column: usize, expected| ParseError {
+        line: None,
+        column,
+        expected,
+        text: String::from_utf8_lossy(line).into_owned(),
+    };
+    match line {
+        [] => Err(fail(1, "a move: 'A', 'B' or 'C'")),
+        [first, ..] if !(b'A'..=b'C').contains(first) => Err(fail(1, "a move: 'A', 'B' or 'C'")),
+        [_] => Err(fail(2, "a single space")),
+        [_, second, ..] if *second != b' ' => Err(fail(2, "a single space")),
+        [_, _] => Err(fail(3, "'X', 'Y' or 'Z'")),
+        [_, _, third, ..] if !(b'X'..=b'Z').contains(third) => Err(fail(3, "'X', 'Y' or 'Z'")),
+        [first, _, third] => Ok((char::from(*first), char::from(*third))),
+        _ => Err(fail(4, "the end of the line")),
+    }
+}
+
+// The start and end of an inclusive range.
+pub(crate) type Range = (u32, u32);
+
+// Two inclusive ranges, e.g. "2-4,6-8".
+pub(crate) fn range_pair(line: &str) -> Result<(Range, Range), ParseError> {
+    fn range(input: &str) -> IResult<&str, Range> {
+        separated_pair(u32, char('-'), u32)(input)
+    }
+    finish(line, "a range pair like 'a-b,c-d'", |input| {
+        separated_pair(range, char(','), range)(input)
+    })
+}
+
+// A crane instruction, e.g. "move 3 from 1 to 2".
+pub(crate) fn move_instruction(line: &str) -> Result<(usize, &str, &str), ParseError> {
+    finish(line, "'move N from A to B'", |input| {
+        map(
+            tuple((
+                preceded(tag("move "), u64),
+                preceded(tag(" from "), token),
+                preceded(tag(" to "), token),
+            )),
+            |(n, src, dst)| (n as usize, src, dst),
+        )(input)
+    })
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) enum TerminalLine<'a> {
+    Cd(&'a str),
+    Ls,
+    Dir(&'a str),
+    File(u64, &'a str),
+}
+
+// One line of a shell transcript: a 'cd' or 'ls' command, or a line of 'ls' output.
+pub(crate) fn terminal_line(line: &str) -> Result<TerminalLine<'_>, ParseError> {
+    finish(line, "'$ cd', '$ ls', 'dir' or a file listing", |input| {
+        alt((
+            map(preceded(tag("$ cd "), token), TerminalLine::Cd),
+            value(TerminalLine::Ls, tag("$ ls")),
+            map(preceded(tag("dir "), token), TerminalLine::Dir),
+            map(separated_pair(u64, space1, token), |(size, name)| {
+                TerminalLine::File(size, name)
+            }),
+        ))(input)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_group_item() {
+        assert_eq!(group_item("1000").unwrap(), Some(1000));
+        assert_eq!(group_item("").unwrap(), None);
+        assert_eq!(
+            group_item("1x").unwrap_err().at_line(2).to_string(),
+            "line 2: expected an integer or a blank line at column 2: \"1x\""
+        );
+    }
+
+    #[test]
+    fn test_line_shapes() {
+        assert_eq!(token_pair("A Y").unwrap(), ("A", "Y"));
+        assert_eq!(range_pair("2-4,6-8").unwrap(), ((2, 4), (6, 8)));
+        assert_eq!(
+            move_instruction("move 3 from 1 to 2").unwrap(),
+            (3, "1", "2")
+        );
+        assert_eq!(range_pair("2-4;6-8").unwrap_err().column, 4);
+        assert!(token_pair("A").is_err());
+        assert_eq!(guide_round(b"A Y").unwrap(), ('A', 'Y'));
+        let columns = [
+            (&b"D Y"[..], 1),
+            (b"A  Y", 3),
+            (b"AY", 2),
+            (b"A W", 3),
+            (b"A Y ", 4),
+            (b"", 1),
+        ];
+        for (line, column) in columns {
+            assert_eq!(guide_round(line).unwrap_err().column, column, "{line:?}");
+        }
+    }
+
+    #[test]
+    fn test_terminal_line() {
+        assert_eq!(terminal_line("$ cd ..").unwrap(), TerminalLine::Cd(".."));
+        assert_eq!(terminal_line("$ ls").unwrap(), TerminalLine::Ls);
+        assert_eq!(terminal_line("dir a").unwrap(), TerminalLine::Dir("a"));
+        assert_eq!(
+            terminal_line("14848514 b.txt").unwrap(),
+            TerminalLine::File(14848514, "b.txt")
+        );
+        assert!(terminal_line("$ rm -rf /").is_err());
+    }
+}
@@ -0,0 +1,229 @@
+use std::fmt::Write;
+use std::panic;
+use std::path::Path;
+use std::time::Duration;
+
+use crate::answers::{self, Status};
+use crate::runner::{self, Checked, Day};
+
+pub(crate) struct DayReport {
+    pub(crate) number: u8,
+    pub(crate) parts: Result<Vec<Checked>, String>,
+    pub(crate) explain: Option<Result<String, String>>,
+}
+
+// Runs every registered day on its main input, bypassing the cache so the timings are real.
+pub(crate) fn collect() -> Vec<DayReport> {
+    runner::DAYS.iter().map(collect_day).collect()
+}
+
+fn collect_day(day: &Day) -> DayReport {
+    let path = runner::input_path(day.number);
+    let parts = answers::load(Path::new(&path))
+        .map_err(|e| e.to_string())
+        .and_then(|known| {
+            let parts = runner::run(day, &path, None).map_err(|e| e.to_string())?;
+            Ok(runner::check(parts, known.as_ref()))
+        });
+    let explain = day.explain.map(|explain| {
+        panic::catch_unwind(|| explain(&path))
+            .map_err(|_| String::from("Explain panicked."))
+            .and_then(|result| result.map_err(|e| e.to_string()))
+    });
+    DayReport {
+        number: day.number,
+        parts,
+        explain,
+    }
+}
+
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for character in text.chars() {
+        match character {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            _ => escaped.push(character),
+        }
+    }
+    escaped
+}
+
+fn status_class(status: Status) -> &'static str {
+    match status {
+        Status::Pass => "pass",
+        Status::Fail => "fail",
+        Status::Unknown => "unknown",
+    }
+}
+
+const STYLE: &str = "
+body { font-family: sans-serif; margin: 2em auto; max-width: 60em; color: #222; }
+table { border-collapse: collapse; width: 100%; margin-bottom: 2em; }
+th, td { border-bottom: 1px solid #ddd; padding: 0.3em 0.6em; text-align: left; }
+td.number { text-align: right; font-variant-numeric: tabular-nums; }
+.pass { color: #1a7f37; } .fail { color: #cf222e; font-weight: bold; } .unknown { color: #888; }
+.chart { margin-bottom: 2em; }
+.bar-row { display: flex; align-items: center; margin: 0.2em 0; }
+.bar-label { width: 8em; } .bar-value { margin-left: 0.5em; white-space: nowrap; }
+.bar { background: #54aeff; height: 1em; min-width: 1px; }
+pre { background: #f6f8fa; padding: 1em; overflow-x: auto; }
+";
+
+// A single page with no external assets: the stylesheet is inlined and the charts are plain 'div's.
+pub(crate) fn render_html(reports: &[DayReport]) -> String {
+    let parts: Vec<&Checked> = reports
+        .iter()
+        .filter_map(|report| report.parts.as_ref().ok())
+        .flatten()
+        .collect();
+    let slowest = parts
+        .iter()
+        .map(|part| part.report.elapsed)
+        .max()
+        .unwrap_or(Duration::ZERO);
+
+    // 'write!' into a 'String' cannot fail, so its 'fmt::Result' is safe to ignore.
+    let mut html = String::new();
+    let _ = write!(
+        html,
+        "<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n\
+         <title>Advent of Code 2022 report</title>\n<style>{STYLE}</style>\n</head>\n<body>\n\
+         <h1>Advent of Code 2022 report</h1>\n"
+    );
+
+    html.push_str("<h2>Answers</h2>\n<table>\n<tr><th>Day</th><th>Part</th><th>Answer</th><th>Expected</th>\
+                   <th>Status</th><th>Time</th><th>Allocs</th><th>Allocated (B)</th><th>Peak (B)</th></tr>\n");
+    for report in reports {
+        match &report.parts {
+            Ok(parts) => {
+                for part in parts {
+                    let memory = part.report.memory.map_or(
+                        [String::from("-"), String::from("-"), String::from("-")],
+                        |stats| {
+                            [
+                                stats.allocations.to_string(),
+                                stats.bytes_allocated.to_string(),
+                                stats.peak_bytes.to_string(),
+                            ]
+                        },
+                    );
+                    let _ = writeln!(
+                        html,
+                        "<tr><td>{:02}</td><td>{}</td><td><code>{}</code></td><td><code>{}</code></td>\
+                         <td class=\"{}\">{}</td><td class=\"number\">{:.2?}</td><td class=\"number\">{}</td>\
+                         <td class=\"number\">{}</td><td class=\"number\">{}</td></tr>",
+                        report.number,
+                        part.report.part,
+                        escape(&part.report.answer),
+                        escape(part.expected.as_deref().unwrap_or("-")),
+                        status_class(part.status),
+                        part.status,
+                        part.report.elapsed,
+                        memory[0],
+                        memory[1],
+                        memory[2]
+                    );
+                }
+            }
+            Err(message) => {
+                let _ = writeln!(
+                    html,
+                    "<tr><td>{:02}</td><td>-</td><td colspan=\"7\" class=\"fail\">{}</td></tr>",
+                    report.number,
+                    escape(message)
+                );
+            }
+        }
+    }
+    html.push_str("</table>\n");
+
+    html.push_str("<h2>Timings</h2>\n<div class=\"chart\">\n");
+    for part in &parts {
+        let width = if slowest.is_zero() {
+            0.0
+        } else {
+            part.report.elapsed.as_secs_f64() / slowest.as_secs_f64() * 100.0
+        };
+        let _ = writeln!(
+            html,
+            "<div class=\"bar-row\"><span class=\"bar-label\">Day {:02} part {}</span>\
+             <div class=\"bar\" style=\"width: {width:.1}%\"></div>\
+             <span class=\"bar-value\">{:.2?}</span></div>",
+            part.report.day, part.report.part, part.report.elapsed
+        );
+    }
+    html.push_str("</div>\n");
+
+    html.push_str("<h2>Explain</h2>\n");
+    for report in reports {
+        if let Some(explain) = &report.explain {
+            let body = match explain {
+                Ok(text) => format!("<pre>{}</pre>", escape(text)),
+                Err(message) => format!("<p class=\"fail\">{}</p>", escape(message)),
+            };
+            let _ = writeln!(
+                html,
+                "<details>\n<summary>Day {:02}</summary>\n{body}\n</details>",
+                report.number
+            );
+        }
+    }
+    html.push_str("</body>\n</html>\n");
+    html
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::runner::PartReport;
+
+    #[test]
+    fn test_escape() {
+        assert_eq!(
+            escape(r#"<a href="x">Tom & Jerry's</a>"#),
+            "&lt;a href=&quot;x&quot;&gt;Tom &amp; Jerry&#39;s&lt;/a&gt;"
+        );
+        assert_eq!(escape("CMZ"), "CMZ");
+    }
+
+    #[test]
+    fn test_render_html() {
+        let part = Checked {
+            report: PartReport {
+                day: 5,
+                part: 1,
+                answer: String::from("<CMZ>"),
+                elapsed: Duration::from_millis(2),
+                memory: None,
+                cached: false,
+            },
+            expected: Some(String::from("CMZ")),
+            status: Status::Fail,
+        };
+        let reports = [
+            DayReport {
+                number: 5,
+                parts: Ok(vec![part]),
+                explain: Some(Ok(String::from("[Z] & [M]"))),
+            },
+            DayReport {
+                number: 6,
+                parts: Err(String::from("Solver panicked: <boom>")),
+                explain: Some(Err(String::from("Explain panicked."))),
+            },
+        ];
+        let html = render_html(&reports);
+        assert!(html.contains("<code>&lt;CMZ&gt;</code>"));
+        assert!(html.contains("<td class=\"fail\">FAIL</td>"));
+        assert!(html.contains("<pre>[Z] &amp; [M]</pre>"));
+        assert!(html.contains("Solver panicked: &lt;boom&gt;"));
+        assert!(!html.contains("<boom>") && !html.contains("<CMZ>"));
+        // Only the part that ran gets a timing bar, and it is the slowest so fills the chart.
+        assert_eq!(html.matches("class=\"bar\"").count(), 1);
+        assert!(html.contains("width: 100.0%"));
+    }
+}
@@ -0,0 +1,382 @@
+use std::error;
+use std::fs;
+use std::panic;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use crate::answers::{self, Answers, Status};
+use crate::bench::Benchmark;
+use crate::cache::{self, Cache};
+use crate::explore::Explorer;
+use crate::memory::{self, AllocStats};
+use crate::viz;
+use crate::{day01, day02, day03, day04, day05, day06, day07};
+
+// A plain function pointer rather than a 'Box<dyn Fn>' - none of the solvers capture anything,
+// so non-capturing closures coerce to 'fn' and the whole registry can live in a 'const'.
+pub(crate) type Solver = fn(&str) -> Result<String, Box<dyn error::Error>>;
+
+pub(crate) type ExplorerFactory = fn(&str) -> Result<Box<dyn Explorer>, Box<dyn error::Error>>;
+
+// Sends frames of the day's state to 'sink' as the solution progresses, for 'run --visualize'.
+pub(crate) type Visualizer = fn(&str, &mut dyn viz::Sink) -> Result<(), Box<dyn error::Error>>;
+
+pub(crate) struct Day {
+    pub(crate) number: u8,
+    // Bump when a change to the solution could change its answers; cached answers computed
+    // by any other version are ignored and evicted.
+    pub(crate) version: u32,
+    pub(crate) part1: Solver,
+    pub(crate) part2: Option<Solver>,
+    // Human-readable intermediate state (parsed structures, final state, statistics) for reports.
+    pub(crate) explain: Option<Solver>,
+    // Loads the day's parsed puzzle state for the interactive 'explore' command.
+    pub(crate) explore: Option<ExplorerFactory>,
+    pub(crate) visualize: Option<Visualizer>,
+    // Generated inputs and alternative implementations for the 'bench' command.
+    pub(crate) bench: Option<Benchmark>,
+}
+
+pub(crate) const DAYS: &[Day] = &[
+    Day {
+        number: 1,
+        version: 2,
+        part1: |path| Ok(day01::part1(path)?.to_string()),
+        part2: Some(|path| Ok(day01::part2(path)?.to_string())),
+        explain: Some(day01::explain),
+        explore: None,
+        visualize: None,
+        bench: Some(Benchmark {
+            unit: "MB",
+            default_size: 1024,
+            generate: day01::generate,
+            contenders: &[
+                ("streaming (top 3)", |path| {
+                    Ok(day01::part2(path)?.to_string())
+                }),
+                ("inventory (top 3)", |path| {
+                    Ok(day01::part2_inventory(path)?.to_string())
+                }),
+            ],
+        }),
+    },
+    Day {
+        number: 2,
+        version: 2,
+        part1: |path| Ok(day02::part1(path)?.to_string()),
+        part2: Some(|path| Ok(day02::part2(path)?.to_string())),
+        explain: None,
+        explore: None,
+        visualize: None,
+        bench: Some(Benchmark {
+            unit: "million rounds",
+            default_size: 100,
+            generate: day02::generate,
+            contenders: &[
+                ("bytes (part 1)", |path| Ok(day02::part1(path)?.to_string())),
+                ("lines (part 1)", |path| {
+                    Ok(day02::part1_lines(path)?.to_string())
+                }),
+            ],
+        }),
+    },
+    Day {
+        number: 3,
+        version: 2,
+        part1: |path| Ok(day03::part1(path)?.to_string()),
+        part2: Some(|path| Ok(day03::part2(path)?.to_string())),
+        explain: None,
+        explore: None,
+        visualize: None,
+        bench: None,
+    },
+    Day {
+        number: 4,
+        version: 2,
+        part1: |path| Ok(day04::part1(path)?.to_string()),
+        part2: Some(|path| Ok(day04::part2(path)?.to_string())),
+        explain: None,
+        explore: None,
+        visualize: None,
+        bench: None,
+    },
+    Day {
+        number: 5,
+        version: 1,
+        part1: |path| Ok(day05::part1(path)),
+        part2: Some(|path| Ok(day05::part2(path))),
+        explain: Some(day05::explain),
+        explore: Some(|path| Ok(Box::new(day05::Explorer::new(path)?))),
+        visualize: Some(day05::visualize),
+        bench: None,
+    },
+    Day {
+        number: 6,
+        version: 2,
+        part1: |path| Ok(day06::part1(path).to_string()),
+        part2: Some(|path| Ok(day06::part2(path).to_string())),
+        explain: None,
+        explore: None,
+        visualize: Some(day06::visualize),
+        bench: None,
+    },
+    Day {
+        number: 7,
+        version: 1,
+        part1: |path| Ok(day07::part1(path)?.to_string()),
+        part2: None,
+        explain: Some(day07::explain),
+        explore: Some(|path| Ok(Box::new(day07::Explorer::new(path)?))),
+        visualize: None,
+        bench: None,
+    },
+];
+
+#[derive(serde::Serialize)]
+pub(crate) struct PartReport {
+    pub(crate) day: u8,
+    pub(crate) part: u8,
+    pub(crate) answer: String,
+    #[serde(rename = "seconds", serialize_with = "serialize_seconds")]
+    pub(crate) elapsed: Duration,
+    pub(crate) memory: Option<AllocStats>,
+    pub(crate) cached: bool,
+}
+
+fn serialize_seconds<S: serde::Serializer>(
+    elapsed: &Duration,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    serializer.serialize_f64(elapsed.as_secs_f64())
+}
+
+pub(crate) fn find(number: u8) -> Option<&'static Day> {
+    DAYS.iter().find(|day| day.number == number)
+}
+
+// A single day when a number is given, otherwise every registered day.
+pub(crate) fn select(number: Option<u8>) -> Result<Vec<&'static Day>, Box<dyn error::Error>> {
+    match number {
+        Some(number) => Ok(vec![
+            find(number).ok_or(format!("Day {number} is not registered."))?
+        ]),
+        None => Ok(DAYS.iter().collect()),
+    }
+}
+
+pub(crate) fn input_path(number: u8) -> String {
+    format!("resources/day{number:02}.txt")
+}
+
+fn solve(
+    day: &Day,
+    part: u8,
+    solver: Solver,
+    path: &str,
+    cache: Option<(&mut Cache, &str)>,
+) -> Result<PartReport, Box<dyn error::Error>> {
+    if let Some((cache, input_hash)) = &cache {
+        if let Some(entry) = cache.get(day.number, part, day.version, input_hash) {
+            tracing::info!(day = day.number, part, "serving cached answer");
+            return Ok(PartReport {
+                day: day.number,
+                part,
+                answer: entry.answer.clone(),
+                elapsed: Duration::from_secs_f64(entry.seconds),
+                memory: entry.memory,
+                cached: true,
+            });
+        }
+    }
+
+    // The solvers 'unwrap' freely, so a malformed input usually panics rather than returning an error.
+    // Catching the panic lets a batch of inputs carry on and report the failure against the right input.
+    let start = Instant::now();
+    let (answer, memory) = memory::measure(|| panic::catch_unwind(|| solver(path)));
+    let elapsed = start.elapsed();
+    let answer = answer
+        .map_err(|payload| panic_message(payload.as_ref()))?
+        .map_err(|e| format!("Day {:02} part {part}: {e}", day.number))?;
+    let report = PartReport {
+        day: day.number,
+        part,
+        answer,
+        elapsed,
+        memory,
+        cached: false,
+    };
+
+    if let Some((cache, input_hash)) = cache {
+        cache.insert(cache::Entry {
+            day: day.number,
+            part,
+            version: day.version,
+            input_hash: String::from(input_hash),
+            answer: report.answer.clone(),
+            seconds: elapsed.as_secs_f64(),
+            memory,
+        });
+    }
+    Ok(report)
+}
+
+fn panic_message(payload: &(dyn std::any::Any + Send)) -> String {
+    let message = payload
+        .downcast_ref::<&str>()
+        .copied()
+        .or_else(|| payload.downcast_ref::<String>().map(String::as_str))
+        .unwrap_or("unknown panic payload");
+    format!("Solver panicked: {message}")
+}
+
+// Passing 'None' for the cache always recomputes and leaves any cache file untouched.
+#[tracing::instrument(skip_all, fields(day = day.number, path))]
+pub(crate) fn run(
+    day: &Day,
+    path: &str,
+    mut cache: Option<&mut Cache>,
+) -> Result<Vec<PartReport>, Box<dyn error::Error>> {
+    let input_hash = match cache {
+        Some(_) => cache::hash_input(&fs::read(path)?),
+        None => String::new(),
+    };
+
+    let mut reports = Vec::new();
+    let part1_cache = cache
+        .as_deref_mut()
+        .map(|cache| (cache, input_hash.as_str()));
+    reports.push(solve(day, 1, day.part1, path, part1_cache)?);
+    if let Some(part2) = day.part2 {
+        let part2_cache = cache.map(|cache| (cache, input_hash.as_str()));
+        reports.push(solve(day, 2, part2, path, part2_cache)?);
+    }
+    Ok(reports)
+}
+
+pub(crate) fn print_table(reports: &[PartReport]) {
+    println!(
+        "{:<4} {:<5} {:<20} {:>12} {:>10} {:>14} {:>14} {:>7}",
+        "Day", "Part", "Answer", "Time", "Allocs", "Allocated (B)", "Peak (B)", "Cached"
+    );
+    for report in reports {
+        // Memory columns are left blank unless the binary was built with '--features alloc-stats'.
+        let (allocations, allocated, peak) = match report.memory {
+            Some(stats) => (
+                stats.allocations.to_string(),
+                stats.bytes_allocated.to_string(),
+                stats.peak_bytes.to_string(),
+            ),
+            None => (String::from("-"), String::from("-"), String::from("-")),
+        };
+        println!(
+            "{:<4} {:<5} {:<20} {:>12} {:>10} {:>14} {:>14} {:>7}",
+            format!("{:02}", report.day),
+            report.part,
+            report.answer,
+            format!("{:.2?}", report.elapsed),
+            allocations,
+            allocated,
+            peak,
+            if report.cached { "yes" } else { "no" }
+        );
+    }
+}
+
+// A part's report checked against a known answer, if there is one.
+pub(crate) struct Checked {
+    pub(crate) report: PartReport,
+    pub(crate) expected: Option<String>,
+    pub(crate) status: Status,
+}
+
+pub(crate) fn check(reports: Vec<PartReport>, known: Option<&Answers>) -> Vec<Checked> {
+    reports
+        .into_iter()
+        .map(|report| {
+            let expected = known
+                .and_then(|known| known.part(report.part))
+                .map(String::from);
+            let status = known.map_or(Status::Unknown, |known| {
+                known.check(report.part, &report.answer)
+            });
+            Checked {
+                report,
+                expected,
+                status,
+            }
+        })
+        .collect()
+}
+
+pub(crate) struct InputReport {
+    pub(crate) input: PathBuf,
+    // The checked parts, or the message of whatever stopped the day from running on this input.
+    pub(crate) result: Result<Vec<Checked>, String>,
+}
+
+impl InputReport {
+    pub(crate) fn passed(&self) -> bool {
+        match &self.result {
+            Ok(parts) => parts.iter().all(|part| part.status != Status::Fail),
+            Err(_) => false,
+        }
+    }
+}
+
+// Runs 'day' on every file in 'directory' (other than '.answers' files), in file name order.
+pub(crate) fn run_inputs(
+    day: &Day,
+    directory: &Path,
+    mut cache: Option<&mut Cache>,
+) -> Result<Vec<InputReport>, Box<dyn error::Error>> {
+    let mut inputs: Vec<PathBuf> = fs::read_dir(directory)?
+        .map(|entry| entry.map(|entry| entry.path()))
+        .collect::<Result<_, _>>()?;
+    inputs.retain(|path| path.is_file() && path.extension().is_none_or(|ext| ext != "answers"));
+    inputs.sort();
+
+    let mut reports = Vec::new();
+    for input in inputs {
+        let path = input.to_string_lossy().into_owned();
+        let result = answers::load(&input)
+            .map_err(|e| e.to_string())
+            .and_then(|known| {
+                let parts = run(day, &path, cache.as_deref_mut()).map_err(|e| e.to_string())?;
+                Ok(check(parts, known.as_ref()))
+            });
+        reports.push(InputReport { input, result });
+    }
+    Ok(reports)
+}
+
+pub(crate) fn print_input_table(reports: &[InputReport]) {
+    println!(
+        "{:<24} {:<5} {:<20} {:<20} {:<6} {:>12}",
+        "Input", "Part", "Answer", "Expected", "Status", "Time"
+    );
+    for report in reports {
+        let name = report
+            .input
+            .file_name()
+            .unwrap_or_default()
+            .to_string_lossy();
+        match &report.result {
+            Ok(parts) => {
+                for part in parts {
+                    println!(
+                        "{:<24} {:<5} {:<20} {:<20} {:<6} {:>12}",
+                        name,
+                        part.report.part,
+                        part.report.answer,
+                        part.expected.as_deref().unwrap_or("-"),
+                        part.status,
+                        format!("{:.2?}", part.report.elapsed)
+                    );
+                }
+            }
+            Err(message) => {
+                println!("{:<24} {:<5} {message}", name, "-");
+            }
+        }
+    }
+}
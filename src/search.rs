@@ -0,0 +1,257 @@
+use std::cmp::Reverse;
+use std::collections::hash_map::Entry;
+use std::collections::{BinaryHeap, HashMap, HashSet, VecDeque};
+use std::hash::Hash;
+
+// How a search treats states it has already reached.
+//  - 'dedup' keeps a hash map of visited states (and, for weighted searches, their best known cost)
+//    so each state is expanded at most once. Turning it off gives a plain tree search, which is only
+//    sensible when the neighbour function cannot lead back to an earlier state.
+//  - 'max_expansions' gives up after expanding that many states, for searches that might explode.
+#[derive(Clone, Copy)]
+pub(crate) struct Options {
+    pub(crate) dedup: bool,
+    pub(crate) max_expansions: Option<usize>,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Self {
+            dedup: true,
+            max_expansions: None,
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub(crate) struct Path<S> {
+    // From the start state to the goal, both included.
+    pub(crate) states: Vec<S>,
+    pub(crate) cost: u64,
+}
+
+// Every reached state is stored once in an arena and refers to its parent by index,
+// so reconstructing the path does not depend on the states being hashable.
+struct Node<S> {
+    state: S,
+    parent: Option<usize>,
+    cost: u64,
+}
+
+fn reconstruct<S: Clone>(nodes: &[Node<S>], mut index: usize) -> Path<S> {
+    let cost = nodes[index].cost;
+    let mut states = vec![nodes[index].state.clone()];
+    while let Some(parent) = nodes[index].parent {
+        states.push(nodes[parent].state.clone());
+        index = parent;
+    }
+    states.reverse();
+    Path { states, cost }
+}
+
+// Breadth-first search over unweighted edges; stops at the first state satisfying 'is_goal',
+// which is then one of the nearest goals (in number of steps) to 'start'.
+pub(crate) fn bfs<S, I>(
+    start: S,
+    mut neighbours: impl FnMut(&S) -> I,
+    mut is_goal: impl FnMut(&S) -> bool,
+    options: Options,
+) -> Option<Path<S>>
+where
+    S: Clone + Eq + Hash,
+    I: IntoIterator<Item = S>,
+{
+    let mut visited = HashSet::new();
+    if options.dedup {
+        visited.insert(start.clone());
+    }
+    let mut nodes = vec![Node {
+        state: start,
+        parent: None,
+        cost: 0,
+    }];
+    let mut queue = VecDeque::from([0]);
+
+    let mut expansions = 0;
+    while let Some(index) = queue.pop_front() {
+        if is_goal(&nodes[index].state) {
+            return Some(reconstruct(&nodes, index));
+        }
+        expansions += 1;
+        if options.max_expansions.is_some_and(|max| expansions > max) {
+            return None;
+        }
+        for next in neighbours(&nodes[index].state) {
+            if options.dedup && !visited.insert(next.clone()) {
+                continue;
+            }
+            nodes.push(Node {
+                state: next,
+                parent: Some(index),
+                cost: nodes[index].cost + 1,
+            });
+            queue.push_back(nodes.len() - 1);
+        }
+    }
+    None
+}
+
+// Dijkstra's algorithm: the cheapest path to a goal over non-negative edge costs.
+// No solved day needs it yet (day 5's search has a heuristic), so only the tests call it.
+#[allow(dead_code)]
+pub(crate) fn dijkstra<S, I>(
+    start: S,
+    neighbours: impl FnMut(&S) -> I,
+    is_goal: impl FnMut(&S) -> bool,
+    options: Options,
+) -> Option<Path<S>>
+where
+    S: Clone + Eq + Hash,
+    I: IntoIterator<Item = (S, u64)>,
+{
+    astar(start, neighbours, |_| 0, is_goal, options)
+}
+
+// A*: Dijkstra guided by 'heuristic', an estimate of the remaining cost to a goal.
+// The path is only guaranteed to be the cheapest when the heuristic never overestimates.
+pub(crate) fn astar<S, I>(
+    start: S,
+    mut neighbours: impl FnMut(&S) -> I,
+    mut heuristic: impl FnMut(&S) -> u64,
+    mut is_goal: impl FnMut(&S) -> bool,
+    options: Options,
+) -> Option<Path<S>>
+where
+    S: Clone + Eq + Hash,
+    I: IntoIterator<Item = (S, u64)>,
+{
+    let mut best_costs: HashMap<S, u64> = HashMap::new();
+    if options.dedup {
+        best_costs.insert(start.clone(), 0);
+    }
+    let mut frontier = BinaryHeap::from([Reverse((heuristic(&start), 0))]);
+    let mut nodes = vec![Node {
+        state: start,
+        parent: None,
+        cost: 0,
+    }];
+
+    let mut expansions = 0;
+    while let Some(Reverse((_, index))) = frontier.pop() {
+        let cost = nodes[index].cost;
+        // A cheaper route to this state was found after this entry was queued.
+        if options.dedup && best_costs[&nodes[index].state] < cost {
+            continue;
+        }
+        if is_goal(&nodes[index].state) {
+            return Some(reconstruct(&nodes, index));
+        }
+        expansions += 1;
+        if options.max_expansions.is_some_and(|max| expansions > max) {
+            return None;
+        }
+        for (next, step_cost) in neighbours(&nodes[index].state) {
+            let next_cost = cost + step_cost;
+            if options.dedup {
+                match best_costs.entry(next.clone()) {
+                    Entry::Occupied(entry) if *entry.get() <= next_cost => continue,
+                    Entry::Occupied(mut entry) => {
+                        entry.insert(next_cost);
+                    }
+                    Entry::Vacant(entry) => {
+                        entry.insert(next_cost);
+                    }
+                }
+            }
+            let priority = next_cost + heuristic(&next);
+            nodes.push(Node {
+                state: next,
+                parent: Some(index),
+                cost: next_cost,
+            });
+            frontier.push(Reverse((priority, nodes.len() - 1)));
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::grid::{Grid, Point};
+
+    const MAZE: &str = "\
+S..#....
+.#.#.##.
+.#...#..
+.####.#.
+......#E";
+
+    fn find(grid: &Grid<char>, target: char) -> Point {
+        (0..grid.height())
+            .flat_map(|y| (0..grid.width()).map(move |x| Point::new(x, y)))
+            .find(|point| grid.get(*point) == Some(&target))
+            .unwrap()
+    }
+
+    #[test]
+    fn test_bfs_shortest_path() {
+        let maze = Grid::parse(MAZE);
+        let (start, end) = (find(&maze, 'S'), find(&maze, 'E'));
+        let open = |point: &Point| maze.get(*point) != Some(&'#');
+        let neighbours = |point: &Point| maze.neighbours4(*point).filter(open).collect::<Vec<_>>();
+
+        let path = bfs(start, neighbours, |point| *point == end, Options::default()).unwrap();
+        assert_eq!(path.cost, 15);
+        assert_eq!(path.states.first(), Some(&start));
+        assert_eq!(path.states.last(), Some(&end));
+        assert!(bfs(start, neighbours, |_| false, Options::default()).is_none());
+    }
+
+    #[test]
+    fn test_weighted_searches_agree() {
+        // Moving right is cheap, every other step is expensive.
+        let neighbours = |&(x, y): &(u32, u32)| {
+            vec![((x + 1, y), 1), ((x, y + 1), 5)]
+                .into_iter()
+                .filter(|((x, y), _)| *x <= 4 && *y <= 4)
+                .collect::<Vec<_>>()
+        };
+        let is_goal = |state: &(u32, u32)| *state == (4, 4);
+        let heuristic = |&(x, y): &(u32, u32)| u64::from(4 - x) + 5 * u64::from(4 - y);
+
+        let shortest = dijkstra((0, 0), neighbours, is_goal, Options::default()).unwrap();
+        let guided = astar((0, 0), neighbours, heuristic, is_goal, Options::default()).unwrap();
+        assert_eq!(shortest.cost, 24);
+        assert_eq!(guided.cost, 24);
+
+        let limited = Options {
+            max_expansions: Some(3),
+            ..Options::default()
+        };
+        assert!(dijkstra((0, 0), neighbours, is_goal, limited).is_none());
+    }
+
+    #[test]
+    fn test_dijkstra_cheapest_path() {
+        // The direct edge a -> d costs more than going round through b and c.
+        let edges = [
+            ('a', 'b', 1),
+            ('b', 'c', 2),
+            ('c', 'd', 3),
+            ('a', 'd', 10),
+            ('b', 'd', 8),
+        ];
+        let neighbours = |node: &char| {
+            edges
+                .iter()
+                .filter(|(from, _, _)| from == node)
+                .map(|(_, to, cost)| (*to, *cost))
+                .collect::<Vec<_>>()
+        };
+        let path = dijkstra('a', neighbours, |node| *node == 'd', Options::default()).unwrap();
+        assert_eq!(path.cost, 6);
+        assert_eq!(path.states, ['a', 'b', 'c', 'd']);
+        assert!(dijkstra('d', neighbours, |node| *node == 'a', Options::default()).is_none());
+    }
+}
@@ -0,0 +1,202 @@
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+use std::thread;
+use std::time::Duration;
+
+use crate::grid::{Grid, Point};
+
+// Moves the cursor home and clears the screen before each frame.
+const CLEAR: &str = "\x1b[H\x1b[2J";
+const RESET: &str = "\x1b[0m";
+
+#[derive(Clone, Copy, PartialEq)]
+pub(crate) enum Colour {
+    Red,
+    Green,
+    Yellow,
+}
+
+impl Colour {
+    fn ansi(self) -> &'static str {
+        match self {
+            Colour::Red => "\x1b[31m",
+            Colour::Green => "\x1b[32m",
+            Colour::Yellow => "\x1b[33m",
+        }
+    }
+}
+
+#[derive(Clone, Copy)]
+struct Cell {
+    symbol: char,
+    colour: Option<Colour>,
+}
+
+// One picture of a day's state: a character grid where any cell may be coloured.
+pub(crate) struct Frame {
+    cells: Grid<Cell>,
+}
+
+impl Frame {
+    // Lines of different lengths are padded with spaces, like 'Grid::parse'.
+    pub(crate) fn from_text(text: &str) -> Self {
+        let symbols = Grid::parse(text);
+        let blank = Cell {
+            symbol: ' ',
+            colour: None,
+        };
+        let mut cells = Grid::new(symbols.width(), symbols.height(), blank);
+        for y in 0..symbols.height() {
+            for x in 0..symbols.width() {
+                let point = Point::new(x, y);
+                if let (Some(cell), Some(symbol)) = (cells.get_mut(point), symbols.get(point)) {
+                    cell.symbol = *symbol;
+                }
+            }
+        }
+        Self { cells }
+    }
+
+    // Points outside the frame are ignored, so callers need not clip highlights themselves.
+    pub(crate) fn paint(&mut self, point: Point, colour: Colour) {
+        if let Some(cell) = self.cells.get_mut(point) {
+            cell.colour = Some(colour);
+        }
+    }
+
+    // ANSI colour codes are only emitted where the colour changes. Lines end in "\r\n" since
+    // a recorded terminal is in raw mode, where "\n" alone does not return the cursor.
+    fn render(&self) -> String {
+        let mut rendered = String::new();
+        for y in 0..self.cells.height() {
+            let mut current = None;
+            for cell in self.cells.row(y).into_iter().flatten() {
+                if cell.colour != current {
+                    rendered.push_str(cell.colour.map_or(RESET, Colour::ansi));
+                    current = cell.colour;
+                }
+                rendered.push(cell.symbol);
+            }
+            if current.is_some() {
+                rendered.push_str(RESET);
+            }
+            rendered.push_str("\r\n");
+        }
+        rendered
+    }
+}
+
+// Where a day's visualisation sends its frames.
+pub(crate) trait Sink {
+    fn frame(&mut self, frame: &Frame) -> io::Result<()>;
+
+    fn finish(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+// The value parser for '--speed': frames per second must be a positive, finite number whose frame
+// interval is a valid 'Duration', since 'Player' and 'CastWriter' divide by it.
+pub(crate) fn parse_speed(text: &str) -> Result<f64, String> {
+    let frames_per_second: f64 = text.parse().map_err(|e| format!("{e}"))?;
+    if !frames_per_second.is_finite() || frames_per_second <= 0.0 {
+        return Err(String::from(
+            "must be a positive number of frames per second",
+        ));
+    }
+    Duration::try_from_secs_f64(1.0 / frames_per_second)
+        .map_err(|_| String::from("is too slow to give a frame interval"))?;
+    Ok(frames_per_second)
+}
+
+// Plays frames straight away in the terminal.
+pub(crate) struct Player<W: Write> {
+    output: W,
+    delay: Duration,
+}
+
+impl<W: Write> Player<W> {
+    pub(crate) fn new(output: W, frames_per_second: f64) -> Self {
+        Self {
+            output,
+            delay: Duration::from_secs_f64(1.0 / frames_per_second),
+        }
+    }
+}
+
+impl<W: Write> Sink for Player<W> {
+    fn frame(&mut self, frame: &Frame) -> io::Result<()> {
+        write!(self.output, "{CLEAR}{}", frame.render())?;
+        self.output.flush()?;
+        thread::sleep(self.delay);
+        Ok(())
+    }
+}
+
+// Records frames as an asciinema (v2) cast file, played back with 'asciinema play'.
+// The header states the terminal size, so frames are kept until 'finish' once the largest is known.
+pub(crate) struct CastWriter {
+    output: BufWriter<File>,
+    interval: f64,
+    frames: Vec<String>,
+    width: usize,
+    height: usize,
+}
+
+impl CastWriter {
+    pub(crate) fn create(path: &Path, frames_per_second: f64) -> io::Result<Self> {
+        Ok(Self {
+            output: BufWriter::new(File::create(path)?),
+            interval: 1.0 / frames_per_second,
+            frames: Vec::new(),
+            width: 0,
+            height: 0,
+        })
+    }
+}
+
+impl Sink for CastWriter {
+    fn frame(&mut self, frame: &Frame) -> io::Result<()> {
+        self.width = self.width.max(frame.cells.width());
+        self.height = self.height.max(frame.cells.height());
+        self.frames.push(format!("{CLEAR}{}", frame.render()));
+        Ok(())
+    }
+
+    fn finish(&mut self) -> io::Result<()> {
+        let header = serde_json::json!({
+            "version": 2,
+            "width": self.width,
+            "height": self.height,
+            "env": {"TERM": "xterm-256color"},
+        });
+        writeln!(self.output, "{header}")?;
+        for (i, frame) in self.frames.iter().enumerate() {
+            let event = (i as f64 * self.interval, "o", frame);
+            writeln!(self.output, "{}", serde_json::to_string(&event)?)?;
+        }
+        self.output.flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render() {
+        let mut frame = Frame::from_text("ab\nc");
+        frame.paint(Point::new(1, 0), Colour::Red);
+        frame.paint(Point::new(5, 5), Colour::Red);
+        assert_eq!(frame.render(), "a\x1b[31mb\x1b[0m\r\nc \r\n");
+    }
+
+    #[test]
+    fn test_parse_speed() {
+        assert_eq!(parse_speed("2.5"), Ok(2.5));
+        for invalid in ["0", "-1", "NaN", "inf", "1e-300", "fast"] {
+            assert!(parse_speed(invalid).is_err(), "{invalid}");
+        }
+    }
+}